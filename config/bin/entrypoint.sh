@@ -1,13 +1,8 @@
 #!/bin/bash
 
-mkdir -p $(dirname "$DATABASE_PATH")
-
-if [ ! -f "$DATABASE_PATH" ]; then
-  db_path=$(mktemp -d)
-  cat /opt/scum-bot/share/sql/*.sql | sqlite3 $db_path/scum-bot.db
-  mv -n $db_path/scum-bot.db "$DATABASE_PATH"
-  rmdir $db_path
-fi
+set -e
+
+/opt/scum-bot/bin/migrate.sh /opt/scum-bot/share/sql
 
 cd /opt/scum-bot/var/
 
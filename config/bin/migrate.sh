@@ -0,0 +1,28 @@
+#!/bin/bash
+#
+# Apply the SQL migrations that the database at $DATABASE_PATH hasn't had yet. Migrations are named
+# NNN-description.sql and are applied in order, each in its own transaction, which also records NNN
+# as the database's user_version.
+
+set -e
+
+sql_path=${1:-/opt/scum-bot/share/sql}
+
+mkdir -p $(dirname "$DATABASE_PATH")
+
+version=$(sqlite3 "$DATABASE_PATH" 'PRAGMA user_version;')
+
+# Databases created before migrations were versioned have the original schema, 001 to 003
+if [ "$version" -eq 0 ] && [ -n "$(sqlite3 "$DATABASE_PATH" "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'channels';")" ]; then
+  version=3
+fi
+
+for migration in "$sql_path"/*.sql; do
+  number=$((10#$(basename "$migration" | cut -d - -f 1)))
+
+  if [ "$number" -gt "$version" ]; then
+    echo "Applying migration $(basename "$migration")"
+    (echo 'BEGIN;'; cat "$migration"; echo "PRAGMA user_version = $number;"; echo 'COMMIT;') \
+      | sqlite3 -bail "$DATABASE_PATH"
+  fi
+done
@@ -0,0 +1 @@
+ALTER TABLE characters ADD COLUMN stress INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+ALTER TABLE characters ADD COLUMN pending_trauma INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE traumas (
+  channel_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  trauma TEXT NOT NULL,
+  PRIMARY KEY (channel_id, user_id, trauma),
+  FOREIGN KEY (channel_id, user_id) REFERENCES characters (channel_id, user_id)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE harm (
+  channel_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  level INTEGER NOT NULL,
+  description TEXT NOT NULL,
+  FOREIGN KEY (channel_id, user_id) REFERENCES characters (channel_id, user_id)
+);
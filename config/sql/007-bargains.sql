@@ -0,0 +1,8 @@
+CREATE TABLE bargains (
+  message_id TEXT PRIMARY KEY,
+  channel_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  bargain TEXT NOT NULL,
+  posted TIMESTAMP NOT NULL,
+  resolved BOOLEAN NOT NULL DEFAULT false
+);
@@ -0,0 +1,7 @@
+CREATE TABLE clocks (
+  channel_id TEXT NOT NULL,
+  name TEXT NOT NULL COLLATE NOCASE,
+  segments INTEGER NOT NULL,
+  ticks INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY (channel_id, name)
+);
@@ -0,0 +1,19 @@
+CREATE TABLE ships (
+  channel_id TEXT PRIMARY KEY,
+
+  engines INTEGER NOT NULL DEFAULT 0,
+  hull INTEGER NOT NULL DEFAULT 0,
+  comms INTEGER NOT NULL DEFAULT 0,
+  weapons INTEGER NOT NULL DEFAULT 0,
+
+  gambits INTEGER NOT NULL DEFAULT 0,
+  cred INTEGER NOT NULL DEFAULT 0,
+  heat INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE ship_upgrades (
+  channel_id TEXT NOT NULL,
+  upgrade TEXT NOT NULL COLLATE NOCASE,
+  PRIMARY KEY (channel_id, upgrade),
+  FOREIGN KEY (channel_id) REFERENCES ships (channel_id)
+);
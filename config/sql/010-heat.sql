@@ -0,0 +1,24 @@
+ALTER TABLE channels ADD COLUMN heat INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE channels ADD COLUMN wanted_level INTEGER NOT NULL DEFAULT 0;
+
+-- Move heat from the ship to the channel
+INSERT OR IGNORE INTO channels (channel_id) SELECT channel_id FROM ships;
+UPDATE channels
+  SET heat = (SELECT heat FROM ships WHERE ships.channel_id = channels.channel_id)
+  WHERE channel_id IN (SELECT channel_id FROM ships);
+
+CREATE TABLE new_ships (
+  channel_id TEXT PRIMARY KEY,
+
+  engines INTEGER NOT NULL DEFAULT 0,
+  hull INTEGER NOT NULL DEFAULT 0,
+  comms INTEGER NOT NULL DEFAULT 0,
+  weapons INTEGER NOT NULL DEFAULT 0,
+
+  gambits INTEGER NOT NULL DEFAULT 0,
+  cred INTEGER NOT NULL DEFAULT 0
+);
+INSERT INTO new_ships (channel_id, engines, hull, comms, weapons, gambits, cred)
+  SELECT channel_id, engines, hull, comms, weapons, gambits, cred FROM ships;
+DROP TABLE ships;
+ALTER TABLE new_ships RENAME TO ships;
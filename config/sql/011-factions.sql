@@ -0,0 +1,12 @@
+CREATE TABLE factions (
+  channel_id TEXT NOT NULL,
+  name TEXT NOT NULL COLLATE NOCASE,
+  tier INTEGER NOT NULL,
+  hold TEXT NOT NULL,
+  status INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY (channel_id, name)
+);
+
+ALTER TABLE ships ADD COLUMN tier INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE clocks ADD COLUMN faction TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE characters ADD COLUMN healing INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE characters ADD COLUMN downtime_activities INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+ALTER TABLE characters ADD COLUMN playbook_xp INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE characters ADD COLUMN insight_xp INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE characters ADD COLUMN prowess_xp INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE characters ADD COLUMN resolve_xp INTEGER NOT NULL DEFAULT 0;
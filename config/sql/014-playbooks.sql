@@ -0,0 +1 @@
+ALTER TABLE characters ADD COLUMN playbook TEXT;
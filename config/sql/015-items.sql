@@ -0,0 +1,11 @@
+ALTER TABLE characters ADD COLUMN loadout TEXT;
+
+CREATE TABLE items (
+  channel_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  name TEXT NOT NULL COLLATE NOCASE,
+  load INTEGER NOT NULL,
+  used BOOLEAN NOT NULL DEFAULT false,
+  PRIMARY KEY (channel_id, user_id, name),
+  FOREIGN KEY (channel_id, user_id) REFERENCES characters (channel_id, user_id)
+);
@@ -0,0 +1,92 @@
+-- Identify characters by their own ID, so that a user may have several named characters in a
+-- channel, one of which is active
+CREATE TABLE new_characters (
+  character_id INTEGER PRIMARY KEY,
+  channel_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  name TEXT NOT NULL COLLATE NOCASE,
+  active BOOLEAN NOT NULL DEFAULT false,
+  playbook TEXT,
+
+  attune INTEGER NOT NULL,
+  command INTEGER NOT NULL,
+  consort INTEGER NOT NULL,
+  doctor INTEGER NOT NULL,
+  hack INTEGER NOT NULL,
+  helm INTEGER NOT NULL,
+  rig INTEGER NOT NULL,
+  scramble INTEGER NOT NULL,
+  scrap INTEGER NOT NULL,
+  skulk INTEGER NOT NULL,
+  study INTEGER NOT NULL,
+  sway INTEGER NOT NULL,
+
+  playbook_xp INTEGER NOT NULL DEFAULT 0,
+  insight_xp INTEGER NOT NULL DEFAULT 0,
+  prowess_xp INTEGER NOT NULL DEFAULT 0,
+  resolve_xp INTEGER NOT NULL DEFAULT 0,
+
+  stress INTEGER NOT NULL DEFAULT 0,
+  pending_trauma INTEGER NOT NULL DEFAULT 0,
+  healing INTEGER NOT NULL DEFAULT 0,
+  downtime_activities INTEGER NOT NULL DEFAULT 0,
+  loadout TEXT,
+
+  UNIQUE (channel_id, user_id, name)
+);
+INSERT INTO new_characters (
+  channel_id, user_id, name, active, playbook,
+  attune, command, consort, doctor, hack, helm, rig, scramble, scrap, skulk, study, sway,
+  playbook_xp, insight_xp, prowess_xp, resolve_xp,
+  stress, pending_trauma, healing, downtime_activities, loadout
+)
+  SELECT
+    channel_id, user_id, COALESCE(playbook, 'Character'), true, playbook,
+    attune, command, consort, doctor, hack, helm, rig, scramble, scrap, skulk, study, sway,
+    playbook_xp, insight_xp, prowess_xp, resolve_xp,
+    stress, pending_trauma, healing, downtime_activities, loadout
+  FROM characters;
+
+CREATE TABLE new_traumas (
+  character_id INTEGER NOT NULL REFERENCES new_characters (character_id),
+  trauma TEXT NOT NULL,
+  PRIMARY KEY (character_id, trauma)
+);
+INSERT INTO new_traumas (character_id, trauma)
+  SELECT new_characters.character_id, traumas.trauma
+  FROM traumas
+  JOIN new_characters USING (channel_id, user_id)
+  ORDER BY traumas.rowid;
+
+CREATE TABLE new_harm (
+  character_id INTEGER NOT NULL REFERENCES new_characters (character_id),
+  level INTEGER NOT NULL,
+  description TEXT NOT NULL
+);
+INSERT INTO new_harm (character_id, level, description)
+  SELECT new_characters.character_id, harm.level, harm.description
+  FROM harm
+  JOIN new_characters USING (channel_id, user_id)
+  ORDER BY harm.rowid;
+
+CREATE TABLE new_items (
+  character_id INTEGER NOT NULL REFERENCES new_characters (character_id),
+  name TEXT NOT NULL COLLATE NOCASE,
+  load INTEGER NOT NULL,
+  used BOOLEAN NOT NULL DEFAULT false,
+  PRIMARY KEY (character_id, name)
+);
+INSERT INTO new_items (character_id, name, load, used)
+  SELECT new_characters.character_id, items.name, items.load, items.used
+  FROM items
+  JOIN new_characters USING (channel_id, user_id)
+  ORDER BY items.rowid;
+
+DROP TABLE traumas;
+DROP TABLE harm;
+DROP TABLE items;
+DROP TABLE characters;
+ALTER TABLE new_characters RENAME TO characters;
+ALTER TABLE new_traumas RENAME TO traumas;
+ALTER TABLE new_harm RENAME TO harm;
+ALTER TABLE new_items RENAME TO items;
@@ -0,0 +1,9 @@
+-- Characters belong to a campaign, which may span several channels. A channel that hasn't been
+-- linked to a campaign is a campaign of its own, identified by the channel's ID, so existing
+-- characters keep their channel's ID as their campaign's ID.
+CREATE TABLE campaign_channels (
+  channel_id TEXT PRIMARY KEY,
+  campaign_id TEXT NOT NULL
+);
+
+ALTER TABLE characters RENAME COLUMN channel_id TO campaign_id;
@@ -0,0 +1,17 @@
+CREATE TABLE rolls (
+  roll_id INTEGER PRIMARY KEY,
+  message_id TEXT NOT NULL,
+  channel_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  character TEXT NULL,
+  check_name TEXT NULL COLLATE NOCASE,
+  kind TEXT NOT NULL,
+  pool INTEGER NOT NULL,
+  mode TEXT NOT NULL,
+  dice TEXT NOT NULL,
+  result INTEGER NOT NULL,
+  outcome TEXT NOT NULL,
+  posted TIMESTAMP NOT NULL
+);
+
+CREATE INDEX rolls_channel_id ON rolls (channel_id, posted);
@@ -0,0 +1 @@
+ALTER TABLE channels ADD COLUMN session_started TIMESTAMP NULL;
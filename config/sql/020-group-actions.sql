@@ -0,0 +1,22 @@
+CREATE TABLE assists (
+  channel_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  assistant_id TEXT NOT NULL,
+  PRIMARY KEY (channel_id, user_id)
+);
+
+CREATE TABLE group_actions (
+  channel_id TEXT PRIMARY KEY,
+  leader_id TEXT NOT NULL,
+  action TEXT NOT NULL,
+  expires TIMESTAMP NOT NULL
+);
+
+CREATE TABLE group_action_participants (
+  channel_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  result INTEGER NOT NULL,
+  outcome TEXT NOT NULL,
+  description TEXT NOT NULL,
+  PRIMARY KEY (channel_id, user_id)
+);
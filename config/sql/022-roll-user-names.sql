@@ -0,0 +1,3 @@
+-- The name of the user who made each roll, so that the roll history can name them without
+-- mentioning them.
+ALTER TABLE rolls ADD COLUMN user_name TEXT;
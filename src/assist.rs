@@ -0,0 +1,71 @@
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, OptionalExtension, Row};
+use serenity::model::id::{ChannelId, UserId};
+
+/// An assist given to a character, which gives them +1d on their next action roll in a channel.
+///
+/// Assists are stored until the assisted character makes an action roll, so that they survive a
+/// restart of the bot.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Assist {
+    pub user_id: UserId,
+    pub assistant_id: UserId,
+}
+
+impl Assist {
+    /// Get the assist given to a user in a channel, if any.
+    pub fn get(
+        connection: &Connection,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> RusqliteResult<Option<Assist>> {
+        connection
+            .query_row(
+                "SELECT user_id, assistant_id FROM assists WHERE channel_id = $1 AND user_id = $2",
+                &[&channel_id.to_string(), &user_id.to_string()],
+                Assist::from_row,
+            )
+            .optional()
+    }
+
+    /// Record an assist given in a channel.
+    pub fn add(
+        connection: &Connection,
+        channel_id: ChannelId,
+        assist: &Assist,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &channel_id.to_string(),
+            &assist.user_id.to_string(),
+            &assist.assistant_id.to_string(),
+        ];
+        connection.execute(
+            "INSERT INTO assists (channel_id, user_id, assistant_id) VALUES ($1, $2, $3)",
+            params,
+        )
+    }
+
+    /// Remove the assist given to a user in a channel, once they've used it.
+    pub fn remove(
+        connection: &Connection,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> RusqliteResult<usize> {
+        connection.execute(
+            "DELETE FROM assists WHERE channel_id = $1 AND user_id = $2",
+            &[&channel_id.to_string(), &user_id.to_string()],
+        )
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Assist> {
+        Ok(Assist {
+            user_id: UserId(row.get::<_, String>("user_id")?.parse::<u64>().unwrap_or(0)),
+            assistant_id: UserId(
+                row.get::<_, String>("assistant_id")?
+                    .parse::<u64>()
+                    .unwrap_or(0),
+            ),
+        })
+    }
+}
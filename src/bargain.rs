@@ -0,0 +1,72 @@
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, Row};
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, UserId};
+use std::fmt;
+
+/// A devil's bargain accepted on an action roll, in exchange for a bonus die.
+///
+/// Bargains are recorded against the message of the roll, so that the GM can review the
+/// outstanding bargains and bring them into play later.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Bargain {
+    pub user_id: UserId,
+    pub bargain: String,
+}
+
+impl Bargain {
+    /// Record a bargain accepted on the roll made by a message.
+    pub fn add(connection: &Connection, message: &Message, bargain: &str) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &message.id.to_string(),
+            &message.channel_id.to_string(),
+            &message.author.id.to_string(),
+            &bargain,
+            &message.timestamp,
+        ];
+        connection.execute(
+            "INSERT INTO bargains (message_id, channel_id, user_id, bargain, posted) VALUES ($1, $2, $3, $4, $5)",
+            params,
+        )
+    }
+
+    /// Get the outstanding bargains accepted in a channel, from oldest to newest.
+    pub fn get_outstanding(
+        connection: &Connection,
+        channel_id: ChannelId,
+    ) -> RusqliteResult<Vec<Bargain>> {
+        let mut statement = connection.prepare(
+            "SELECT user_id, bargain \
+             FROM bargains \
+             WHERE channel_id = $1 \
+             AND NOT resolved \
+             ORDER BY posted",
+        )?;
+        let bargains = statement
+            .query_map(&[&channel_id.to_string()], Bargain::from_row)?
+            .collect();
+        bargains
+    }
+
+    /// Mark all outstanding bargains accepted in a channel as resolved.
+    pub fn resolve_all(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<usize> {
+        connection.execute(
+            "UPDATE bargains SET resolved = true WHERE channel_id = $1 AND NOT resolved",
+            &[&channel_id.to_string()],
+        )
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Bargain> {
+        Ok(Bargain {
+            user_id: UserId(row.get::<_, String>("user_id")?.parse::<u64>().unwrap_or(0)),
+            bargain: row.get("bargain")?,
+        })
+    }
+}
+
+impl fmt::Display for Bargain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<@{}> \"{}\"", self.user_id, self.bargain)
+    }
+}
@@ -0,0 +1,131 @@
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, OptionalExtension};
+use serenity::model::id::{ChannelId, GuildId};
+
+/// An SQL expression for the ID of the campaign that the channel given by the first parameter
+/// belongs to.
+///
+/// A channel that hasn't been linked to a campaign is a campaign of its own, identified by the
+/// channel's ID, so characters created before campaigns existed keep working.
+pub const CAMPAIGN_ID: &str =
+    "COALESCE((SELECT campaign_id FROM campaign_channels WHERE channel_id = $1), $1)";
+
+/// The campaign that a channel is linked to, grouping several channels so that they share
+/// character sheets.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Campaign {
+    /// The campaign of another channel, identified by that channel's ID.
+    Channel(ChannelId),
+    /// The campaign shared by the channels of a guild that have joined it.
+    Guild(GuildId),
+}
+
+impl Campaign {
+    /// The ID of a guild's campaign, which is prefixed so that it can't be mistaken for the
+    /// campaign of a channel that has the same ID as its guild.
+    pub fn guild_campaign_id(guild_id: GuildId) -> String {
+        format!("guild:{}", guild_id)
+    }
+
+    /// Get the ID of the campaign that a channel belongs to.
+    pub fn get_id(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<String> {
+        connection.query_row(
+            &format!("SELECT {}", CAMPAIGN_ID),
+            &[&channel_id.to_string()],
+            |row| row.get(0),
+        )
+    }
+
+    /// Get the channels that have been linked to a campaign, not including the channel that a
+    /// campaign is named after.
+    pub fn get_channels(
+        connection: &Connection,
+        campaign_id: &str,
+    ) -> RusqliteResult<Vec<ChannelId>> {
+        let mut statement = connection.prepare(
+            "SELECT channel_id \
+             FROM campaign_channels \
+             WHERE campaign_id = $1 \
+             AND channel_id != campaign_id \
+             ORDER BY rowid",
+        )?;
+        let channels = statement
+            .query_map(&[&campaign_id], |row| row.get::<_, String>(0))?
+            .filter_map(|channel_id| match channel_id {
+                Ok(channel_id) => channel_id.parse::<u64>().ok().map(|id| Ok(ChannelId(id))),
+                Err(error) => Some(Err(error)),
+            })
+            .collect();
+        channels
+    }
+
+    /// Link a channel to a campaign.
+    ///
+    /// If the channel was a campaign of its own that no other channel is linked to, its
+    /// characters are moved to the new campaign. A moved character stops being active if the user
+    /// already has a character in the new campaign, and characters whose names clash with a
+    /// character already in the new campaign are left behind.
+    ///
+    /// Returns the number of characters moved.
+    pub fn link(
+        connection: &mut Connection,
+        channel_id: ChannelId,
+        campaign: Campaign,
+    ) -> RusqliteResult<usize> {
+        let transaction = connection.transaction()?;
+        let old_campaign_id = Campaign::get_id(&transaction, channel_id)?;
+        let new_campaign_id = match campaign {
+            Campaign::Channel(campaign_channel_id) => {
+                Campaign::get_id(&transaction, campaign_channel_id)?
+            }
+            Campaign::Guild(guild_id) => Campaign::guild_campaign_id(guild_id),
+        };
+        let params: &[&dyn ToSql] = &[&channel_id.to_string(), &new_campaign_id];
+        transaction.execute(
+            "INSERT INTO campaign_channels (channel_id, campaign_id) VALUES ($1, $2) \
+             ON CONFLICT (channel_id) \
+             DO UPDATE SET campaign_id = excluded.campaign_id",
+            params,
+        )?;
+        let shared = transaction
+            .query_row(
+                "SELECT 1 FROM campaign_channels WHERE campaign_id = $1 LIMIT 1",
+                &[&old_campaign_id],
+                |row| row.get::<_, i32>(0),
+            )
+            .optional()?
+            .is_some();
+        let moved = if old_campaign_id == channel_id.to_string()
+            && old_campaign_id != new_campaign_id
+            && !shared
+        {
+            transaction.execute(
+                "UPDATE OR IGNORE characters \
+                 SET campaign_id = $1, \
+                 active = active AND NOT EXISTS ( \
+                 SELECT 1 FROM characters AS others \
+                 WHERE others.campaign_id = $1 \
+                 AND others.user_id = characters.user_id \
+                 AND others.active \
+                 ) \
+                 WHERE campaign_id = $2",
+                &[&new_campaign_id, &old_campaign_id],
+            )?
+        } else {
+            0
+        };
+        transaction.commit().map(|_| moved)
+    }
+
+    /// Unlink a channel from its campaign, so that it's a campaign of its own again.
+    ///
+    /// Returns the number of rows deleted, which is zero if the channel wasn't linked to a
+    /// campaign.
+    pub fn unlink(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<usize> {
+        connection.execute(
+            "DELETE FROM campaign_channels WHERE channel_id = $1",
+            &[&channel_id.to_string()],
+        )
+    }
+}
@@ -1,18 +1,26 @@
+use crate::heat::HeatChange;
+use rusqlite::types::ToSql;
 use rusqlite::Result as RusqliteResult;
 use rusqlite::{Connection, OptionalExtension, Row};
+use serenity::model::channel::Message;
 use serenity::model::id::ChannelId;
+use std::convert::TryInto;
 
 pub struct Channel {
     pub enabled: bool,
     pub locked: bool,
     pub dice_only: bool,
+    pub heat: usize,
+    pub wanted_level: usize,
 }
 
 impl Channel {
     pub fn get(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<Option<Channel>> {
         connection
             .query_row(
-                "SELECT enabled, locked, dice_only FROM channels WHERE channel_id = $1",
+                "SELECT enabled, locked, dice_only, heat, wanted_level \
+                 FROM channels \
+                 WHERE channel_id = $1",
                 &[&channel_id.to_string()],
                 Channel::from_row,
             )
@@ -24,6 +32,56 @@ impl Channel {
             enabled: row.get("enabled")?,
             locked: row.get("locked")?,
             dice_only: row.get("dice_only")?,
+            heat: row.get::<_, i32>("heat")?.try_into().unwrap_or(0),
+            wanted_level: row.get::<_, i32>("wanted_level")?.try_into().unwrap_or(0),
         })
     }
+
+    /// Update the heat and wanted level of the crew playing in a channel.
+    pub fn update_heat(
+        connection: &Connection,
+        channel_id: ChannelId,
+        change: &HeatChange,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &channel_id.to_string(),
+            &(change.heat as i32),
+            &(change.wanted_level as i32),
+        ];
+        connection.execute(
+            "INSERT INTO channels (channel_id, heat, wanted_level) VALUES ($1, $2, $3) \
+             ON CONFLICT (channel_id) \
+             DO UPDATE SET heat = excluded.heat, wanted_level = excluded.wanted_level",
+            params,
+        )
+    }
+
+    /// Start a session in a channel at the time of a message, so that the rolls made from then on
+    /// can be recapped.
+    pub fn start_session(connection: &Connection, message: &Message) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&message.channel_id.to_string(), &message.timestamp];
+        connection.execute(
+            "INSERT INTO channels (channel_id, session_started) VALUES ($1, $2) \
+             ON CONFLICT (channel_id) \
+             DO UPDATE SET session_started = excluded.session_started",
+            params,
+        )
+    }
+
+    /// Get the time that the current session in a channel started, if one has been started.
+    pub fn get_session_started(
+        connection: &Connection,
+        channel_id: ChannelId,
+    ) -> RusqliteResult<Option<String>> {
+        connection
+            .query_row(
+                "SELECT strftime('%Y-%m-%d %H:%M', session_started) \
+                 FROM channels \
+                 WHERE channel_id = $1",
+                &[&channel_id.to_string()],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()
+            .map(Option::flatten)
+    }
 }
@@ -1,14 +1,65 @@
+use crate::campaign::CAMPAIGN_ID;
+use crate::harm::Harm;
+use crate::item::{Item, Loadout};
+use crate::playbook::Playbook;
+use crate::trauma::{Trauma, MAXIMUM_TRAUMAS};
+use rusqlite::types::ToSql;
 use rusqlite::Result as RusqliteResult;
 use rusqlite::{Connection, OptionalExtension, Row};
 use serenity::model::id::{ChannelId, UserId};
 use std::convert::TryInto;
+use std::fmt;
+
+/// The columns of the `characters` table that are read into a `Character`.
+const COLUMNS: &str = "\
+    character_id, \
+    name, \
+    active, \
+    playbook, \
+    attune, \
+    command, \
+    consort, \
+    doctor, \
+    hack, \
+    helm, \
+    rig, \
+    scramble, \
+    scrap, \
+    skulk, \
+    study, \
+    sway, \
+    playbook_xp, \
+    insight_xp, \
+    prowess_xp, \
+    resolve_xp, \
+    stress, \
+    pending_trauma, \
+    healing, \
+    downtime_activities, \
+    loadout";
+
+/// The maximum rating that an action may have.
+pub const MAXIMUM_ACTION_RATING: usize = 4;
+
+/// The maximum stress that a character may have before suffering trauma.
+pub const MAXIMUM_STRESS: usize = 9;
+
+/// The number of segments in a character's healing clock.
+pub const HEALING_CLOCK_SEGMENTS: usize = 4;
 
 /// A character in a Scum and Villainy campaign.
 ///
 /// The character has a number of action ratings, from which action and resistance rolls are
-/// calculated.
-#[derive(Debug, Eq, PartialEq)]
+/// calculated, and a stress track that is marked when resisting consequences. When stress
+/// overflows the character suffers trauma, and must choose a trauma condition to mark. Harm
+/// suffered by the character penalises their rolls.
+#[derive(Debug, Default, Eq, PartialEq)]
 pub struct Character {
+    id: i64,
+    name: String,
+    active: bool,
+    playbook: Option<String>,
+
     // Action ratings
     attune: i32,
     command: i32,
@@ -22,17 +73,149 @@ pub struct Character {
     skulk: i32,
     study: i32,
     sway: i32,
+
+    // Experience
+    playbook_xp: i32,
+    insight_xp: i32,
+    prowess_xp: i32,
+    resolve_xp: i32,
+
+    stress: i32,
+    pending_trauma: i32,
+    healing: i32,
+    downtime_activities: i32,
+    loadout: Option<Loadout>,
+    traumas: Vec<Trauma>,
+    harm: Vec<Harm>,
+    items: Vec<Item>,
 }
 
 impl Character {
+    /// Get a user's active character in the campaign that a channel belongs to.
     pub fn get(
         connection: &Connection,
         channel_id: ChannelId,
         user_id: UserId,
+    ) -> RusqliteResult<Option<Character>> {
+        Character::query(
+            connection,
+            &format!("campaign_id = {} AND user_id = $2 AND active", CAMPAIGN_ID),
+            &[&channel_id.to_string(), &user_id.to_string()],
+        )
+    }
+
+    /// Get one of a user's characters in the campaign that a channel belongs to by name, ignoring
+    /// case.
+    pub fn get_by_name(
+        connection: &Connection,
+        channel_id: ChannelId,
+        user_id: UserId,
+        name: &str,
+    ) -> RusqliteResult<Option<Character>> {
+        Character::query(
+            connection,
+            &format!("campaign_id = {} AND user_id = $2 AND name = $3", CAMPAIGN_ID),
+            &[&channel_id.to_string(), &user_id.to_string(), &name],
+        )
+    }
+
+    /// Get every character that a user has in the campaign that a channel belongs to, in the order
+    /// they were created, without their traumas, harm or items.
+    pub fn get_all(
+        connection: &Connection,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> RusqliteResult<Vec<Character>> {
+        let mut statement = connection.prepare(&format!(
+            "SELECT {} FROM characters WHERE campaign_id = {} AND user_id = $2 ORDER BY character_id",
+            COLUMNS, CAMPAIGN_ID
+        ))?;
+        let characters = statement
+            .query_map(
+                &[&channel_id.to_string(), &user_id.to_string()],
+                Character::from_row,
+            )?
+            .collect();
+        characters
+    }
+
+    fn query(
+        connection: &Connection,
+        condition: &str,
+        params: &[&dyn ToSql],
     ) -> RusqliteResult<Option<Character>> {
         connection
             .query_row(
-                "SELECT \
+                &format!("SELECT {} FROM characters WHERE {}", COLUMNS, condition),
+                params,
+                Character::from_row,
+            )
+            .optional()
+            .and_then(|character| match character {
+                Some(character) => Ok(Some(Character {
+                    traumas: Trauma::get_all(connection, character.id)?,
+                    harm: Harm::get_all(connection, character.id)?,
+                    items: Item::get_all(connection, character.id)?,
+                    ..character
+                })),
+                None => Ok(None),
+            })
+    }
+
+    /// Create a named character, with starting action ratings from a playbook, or with every
+    /// action rating set to zero if no playbook is given. The new character becomes the user's
+    /// active character.
+    ///
+    /// Returns the number of rows inserted, which is zero if the user already has a character in
+    /// the channel with that name.
+    pub fn create(
+        connection: &mut Connection,
+        channel_id: ChannelId,
+        user_id: UserId,
+        name: &str,
+        playbook: Option<&Playbook>,
+    ) -> RusqliteResult<usize> {
+        let rating = |action| playbook.map_or(0, |playbook| playbook.action_rating(action) as i32);
+        let ratings = [
+            rating(ActionName::Attune),
+            rating(ActionName::Command),
+            rating(ActionName::Consort),
+            rating(ActionName::Doctor),
+            rating(ActionName::Hack),
+            rating(ActionName::Helm),
+            rating(ActionName::Rig),
+            rating(ActionName::Scramble),
+            rating(ActionName::Scrap),
+            rating(ActionName::Skulk),
+            rating(ActionName::Study),
+            rating(ActionName::Sway),
+        ];
+        let params: &[&dyn ToSql] = &[
+            &channel_id.to_string(),
+            &user_id.to_string(),
+            &name,
+            &playbook.map(|playbook| playbook.name.as_str()),
+            &ratings[0],
+            &ratings[1],
+            &ratings[2],
+            &ratings[3],
+            &ratings[4],
+            &ratings[5],
+            &ratings[6],
+            &ratings[7],
+            &ratings[8],
+            &ratings[9],
+            &ratings[10],
+            &ratings[11],
+        ];
+        let transaction = connection.transaction()?;
+        let inserted = transaction.execute(
+            &format!(
+                "INSERT OR IGNORE INTO characters ( \
+                 campaign_id, \
+                 user_id, \
+                 name, \
+                 playbook, \
                  attune, \
                  command, \
                  consort, \
@@ -45,17 +228,103 @@ impl Character {
                  skulk, \
                  study, \
                  sway \
-                 FROM characters \
-                 WHERE channel_id = $1 \
-                 AND user_id = $2",
-                &[&channel_id.to_string(), &user_id.to_string()],
-                Character::from_row,
-            )
-            .optional()
+                 ) VALUES ({}, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)",
+                CAMPAIGN_ID
+            ),
+            params,
+        )?;
+        if inserted > 0 {
+            Character::activate(&transaction, channel_id, user_id, name)?;
+        }
+        transaction.commit().map(|_| inserted)
+    }
+
+    /// Make one of a user's characters in a channel their active character.
+    ///
+    /// Returns the number of rows updated, which is zero if the user has no character in the
+    /// channel with that name.
+    pub fn set_active(
+        connection: &mut Connection,
+        channel_id: ChannelId,
+        user_id: UserId,
+        name: &str,
+    ) -> RusqliteResult<usize> {
+        let transaction = connection.transaction()?;
+        let updated = Character::activate(&transaction, channel_id, user_id, name)?;
+        transaction.commit().map(|_| updated)
+    }
+
+    fn activate(
+        connection: &Connection,
+        channel_id: ChannelId,
+        user_id: UserId,
+        name: &str,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&channel_id.to_string(), &user_id.to_string(), &name];
+        let updated = connection.execute(
+            &format!(
+                "UPDATE characters SET active = true \
+                 WHERE campaign_id = {} AND user_id = $2 AND name = $3",
+                CAMPAIGN_ID
+            ),
+            params,
+        )?;
+        if updated > 0 {
+            connection.execute(
+                &format!(
+                    "UPDATE characters SET active = false \
+                     WHERE campaign_id = {} AND user_id = $2 AND name != $3",
+                    CAMPAIGN_ID
+                ),
+                params,
+            )?;
+        }
+        Ok(updated)
+    }
+
+    /// Get the names of the playbooks of the characters in the campaign that a channel belongs
+    /// to.
+    pub fn get_playbooks(
+        connection: &Connection,
+        channel_id: ChannelId,
+    ) -> RusqliteResult<Vec<String>> {
+        let mut statement = connection.prepare(&format!(
+            "SELECT DISTINCT playbook \
+             FROM characters \
+             WHERE campaign_id = {} \
+             AND playbook IS NOT NULL \
+             ORDER BY playbook",
+            CAMPAIGN_ID
+        ))?;
+        let playbooks = statement
+            .query_map(&[&channel_id.to_string()], |row| row.get(0))?
+            .collect();
+        playbooks
+    }
+
+    /// Update a single action rating of a character.
+    pub fn set_action(
+        connection: &Connection,
+        character_id: i64,
+        name: ActionName,
+        rating: usize,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&(rating as i32), &character_id];
+        connection.execute(
+            &format!(
+                "UPDATE characters SET {} = $1 WHERE character_id = $2",
+                name.column_name()
+            ),
+            params,
+        )
     }
 
     pub fn from_row(row: &Row) -> RusqliteResult<Character> {
         Ok(Character {
+            id: row.get("character_id")?,
+            name: row.get("name")?,
+            active: row.get("active")?,
+            playbook: row.get("playbook")?,
             attune: row.get("attune")?,
             command: row.get("command")?,
             consort: row.get("consort")?,
@@ -68,9 +337,287 @@ impl Character {
             skulk: row.get("skulk")?,
             study: row.get("study")?,
             sway: row.get("sway")?,
+            playbook_xp: row.get("playbook_xp")?,
+            insight_xp: row.get("insight_xp")?,
+            prowess_xp: row.get("prowess_xp")?,
+            resolve_xp: row.get("resolve_xp")?,
+            stress: row.get("stress")?,
+            pending_trauma: row.get("pending_trauma")?,
+            healing: row.get("healing")?,
+            downtime_activities: row.get("downtime_activities")?,
+            loadout: row
+                .get::<_, Option<String>>("loadout")?
+                .and_then(|loadout| Loadout::parse(&loadout)),
+            traumas: Vec::new(),
+            harm: Vec::new(),
+            items: Vec::new(),
         })
     }
 
+    /// Update the stress of a character after taking or clearing stress, recording a pending
+    /// trauma if their stress overflowed.
+    pub fn update_stress(
+        connection: &Connection,
+        character_id: i64,
+        change: &StressChange,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &(change.stress as i32),
+            &(change.trauma as i32),
+            &character_id,
+        ];
+        connection.execute(
+            "UPDATE characters \
+             SET stress = $1, pending_trauma = pending_trauma + $2 \
+             WHERE character_id = $3",
+            params,
+        )
+    }
+
+    /// Mark experience on one of a character's XP tracks, which can't go beyond the track's
+    /// maximum.
+    pub fn add_xp(
+        connection: &Connection,
+        character_id: i64,
+        track: XpTrack,
+        amount: usize,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &(amount as i32),
+            &(track.maximum() as i32),
+            &character_id,
+        ];
+        connection.execute(
+            &format!(
+                "UPDATE characters SET {0} = MIN({0} + $1, $2) WHERE character_id = $3",
+                track.column_name()
+            ),
+            params,
+        )
+    }
+
+    /// Update the number of ticks on a character's healing clock.
+    pub fn set_healing(
+        connection: &Connection,
+        character_id: i64,
+        healing: usize,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&(healing as i32), &character_id];
+        connection.execute(
+            "UPDATE characters SET healing = $1 WHERE character_id = $2",
+            params,
+        )
+    }
+
+    /// Record that a character has taken a downtime activity in the current downtime phase.
+    pub fn use_downtime_activity(
+        connection: &Connection,
+        character_id: i64,
+    ) -> RusqliteResult<usize> {
+        connection.execute(
+            "UPDATE characters \
+             SET downtime_activities = downtime_activities + 1 \
+             WHERE character_id = $1",
+            &[&character_id],
+        )
+    }
+
+    /// Start a new downtime phase for every character in the campaign that a channel belongs to,
+    /// allowing them to take their downtime activities again.
+    pub fn reset_downtime_activities(
+        connection: &Connection,
+        channel_id: ChannelId,
+    ) -> RusqliteResult<usize> {
+        connection.execute(
+            &format!(
+                "UPDATE characters SET downtime_activities = 0 WHERE campaign_id = {}",
+                CAMPAIGN_ID
+            ),
+            &[&channel_id.to_string()],
+        )
+    }
+
+    // Load
+
+    /// Choose the load that a character carries on the current job.
+    pub fn set_loadout(
+        connection: &Connection,
+        character_id: i64,
+        loadout: Loadout,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&loadout.as_str().to_lowercase(), &character_id];
+        connection.execute(
+            "UPDATE characters SET loadout = $1 WHERE character_id = $2",
+            params,
+        )
+    }
+
+    /// Clear the load chosen by every character in the campaign that a channel belongs to, at the
+    /// end of a job.
+    pub fn reset_loadouts(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<usize> {
+        connection.execute(
+            &format!(
+                "UPDATE characters SET loadout = NULL WHERE campaign_id = {}",
+                CAMPAIGN_ID
+            ),
+            &[&channel_id.to_string()],
+        )
+    }
+
+    pub fn loadout(&self) -> Option<Loadout> {
+        self.loadout
+    }
+
+    /// Find an item on the character's item list by name, ignoring case.
+    pub fn item(&self, name: &str) -> Option<&Item> {
+        self.items
+            .iter()
+            .find(|item| item.name.to_lowercase() == name.to_lowercase())
+    }
+
+    /// The total load of the items the character has used on the current job.
+    pub fn load(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.used)
+            .map(|item| item.load)
+            .sum()
+    }
+
+    /// The character's load and item list, for display.
+    pub fn gear(&self) -> Gear<'_> {
+        Gear(self)
+    }
+
+    // Experience
+
+    pub fn xp(&self, track: XpTrack) -> usize {
+        match track {
+            XpTrack::Playbook => self.playbook_xp,
+            XpTrack::Insight => self.insight_xp,
+            XpTrack::Prowess => self.prowess_xp,
+            XpTrack::Resolve => self.resolve_xp,
+        }
+        .try_into()
+        .unwrap_or(0)
+    }
+
+    /// Calculate the result of marking an amount of experience on one of the character's tracks.
+    ///
+    /// The track can't go beyond its maximum, so the amount marked may be less than asked for, or
+    /// zero if the track is already full.
+    pub fn apply_xp(&self, track: XpTrack, amount: usize) -> XpChange {
+        let amount = amount.min(track.maximum().saturating_sub(self.xp(track)));
+        XpChange {
+            track,
+            amount,
+            xp: self.xp(track) + amount,
+        }
+    }
+
+    /// Advance an action rating by spending a full XP track, clearing the track.
+    pub fn advance(
+        connection: &Connection,
+        character_id: i64,
+        action: ActionName,
+        track: XpTrack,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&(MAXIMUM_ACTION_RATING as i32), &character_id];
+        connection.execute(
+            &format!(
+                "UPDATE characters SET {0} = MIN({0} + 1, $1), {1} = 0 WHERE character_id = $2",
+                action.column_name(),
+                track.column_name()
+            ),
+            params,
+        )
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether this is the user's active character, which is used by default.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The character's playbook, if they were created from one.
+    pub fn playbook(&self) -> Option<&'static Playbook> {
+        self.playbook
+            .as_ref()
+            .and_then(|playbook| Playbook::get(playbook))
+    }
+
+    // Downtime
+
+    /// The number of ticks on the character's healing clock.
+    pub fn healing(&self) -> usize {
+        self.healing.try_into().unwrap_or(0)
+    }
+
+    /// The number of downtime activities the character has taken in the current downtime phase.
+    pub fn downtime_activities(&self) -> usize {
+        self.downtime_activities.try_into().unwrap_or(0)
+    }
+
+    // Stress and trauma
+
+    pub fn stress(&self) -> usize {
+        self.stress.try_into().unwrap_or(0)
+    }
+
+    /// The number of traumas the character has suffered but not yet chosen a condition for.
+    pub fn pending_trauma(&self) -> usize {
+        self.pending_trauma.try_into().unwrap_or(0)
+    }
+
+    pub fn traumas(&self) -> &[Trauma] {
+        &self.traumas
+    }
+
+    /// Whether the character has suffered enough trauma that they must retire.
+    pub fn must_retire(&self) -> bool {
+        self.traumas.len() >= MAXIMUM_TRAUMAS
+    }
+
+    /// Calculate the result of taking (or, if negative, clearing) an amount of stress.
+    ///
+    /// If the stress would exceed the maximum, the character suffers trauma and their stress is
+    /// cleared.
+    pub fn apply_stress(&self, amount: i32) -> StressChange {
+        let stress = (self.stress + amount).max(0);
+        if stress > MAXIMUM_STRESS as i32 {
+            StressChange {
+                amount,
+                stress: 0,
+                trauma: true,
+            }
+        } else {
+            StressChange {
+                amount,
+                stress: stress as usize,
+                trauma: false,
+            }
+        }
+    }
+
+    // Harm
+
+    /// The harm the character suffers, from most to least severe.
+    pub fn harm(&self) -> &[Harm] {
+        &self.harm
+    }
+
+    /// Whether the character suffers any harm at a level.
+    pub fn has_harm(&self, level: usize) -> bool {
+        self.harm.iter().any(|harm| harm.level == level)
+    }
+
     // Attributes
 
     pub fn attribute(&self, name: AttributeName) -> Option<AttributeRating> {
@@ -93,6 +640,18 @@ impl Character {
         Character::make_attribute(&[self.attune, self.command, self.consort, self.sway])
     }
 
+    /// The attribute with the lowest rating, preferring the first of any that are tied.
+    pub fn lowest_attribute(&self) -> AttributeName {
+        let mut lowest = AttributeName::Insight;
+        for attribute in AttributeName::ALL.iter() {
+            let rating = self.attribute(*attribute).map_or(0, |rating| rating.rating);
+            if rating < self.attribute(lowest).map_or(0, |rating| rating.rating) {
+                lowest = *attribute;
+            }
+        }
+        lowest
+    }
+
     fn make_attribute(ratings: &[i32]) -> Option<AttributeRating> {
         Some(AttributeRating {
             rating: ratings.iter().filter(|r| **r > 0).count(),
@@ -166,6 +725,21 @@ impl Character {
         self.make_action(self.sway)
     }
 
+    /// The action with the highest rating, preferring the first on the character sheet of any
+    /// that are tied.
+    pub fn best_action(&self) -> ActionName {
+        let mut best = ActionName::Doctor;
+        for attribute in AttributeName::ALL.iter() {
+            for action in attribute.actions().iter() {
+                let rating = self.action(*action).map_or(0, |rating| rating.rating);
+                if rating > self.action(best).map_or(0, |rating| rating.rating) {
+                    best = *action;
+                }
+            }
+        }
+        best
+    }
+
     fn make_action(&self, rating: i32) -> Option<ActionRating> {
         Some(ActionRating {
             rating: rating.try_into().unwrap_or(0),
@@ -173,6 +747,191 @@ impl Character {
     }
 }
 
+impl fmt::Display for Character {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "**{}**", self.name)?;
+        if let Some(playbook) = self.playbook.as_ref() {
+            write!(f, " ({})", playbook)?;
+        }
+        writeln!(f)?;
+        for attribute in AttributeName::ALL.iter() {
+            write!(
+                f,
+                "**{}** {}:",
+                attribute.as_str(),
+                self.attribute(*attribute).map_or(0, |rating| rating.rating)
+            )?;
+            for action in attribute.actions().iter() {
+                write!(
+                    f,
+                    " {} {}",
+                    action.as_str(),
+                    Dots(self.action(*action).map_or(0, |rating| rating.rating))
+                )?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "**XP**")?;
+        for (index, track) in XpTrack::ALL.iter().enumerate() {
+            write!(
+                f,
+                "{} {} {}/{}",
+                if index > 0 { "," } else { "" },
+                track.as_str(),
+                self.xp(*track),
+                track.maximum()
+            )?;
+        }
+        write!(f, "\n**Stress** {}/{}", self.stress(), MAXIMUM_STRESS)?;
+        write!(f, "\n**Trauma** ")?;
+        if self.traumas.is_empty() {
+            write!(f, "None")?;
+        } else {
+            for (index, trauma) in self.traumas.iter().enumerate() {
+                write!(f, "{}{}", if index > 0 { ", " } else { "" }, trauma.as_str())?;
+            }
+        }
+        write!(f, " ({}/{})", self.traumas.len(), MAXIMUM_TRAUMAS)?;
+        if self.pending_trauma() > 0 {
+            write!(f, " — choose a trauma with `!trauma <condition>`")?;
+        }
+        if self.must_retire() {
+            write!(f, " — must retire")?;
+        }
+        write!(f, "\n**Harm** ")?;
+        if self.harm.is_empty() {
+            write!(f, "None")?;
+        } else {
+            for (index, harm) in self.harm.iter().enumerate() {
+                write!(f, "{}{}", if index > 0 { ", " } else { "" }, harm)?;
+            }
+        }
+        write!(f, "\n{}", self.gear())
+    }
+}
+
+/// Renders a character's load and item list.
+pub struct Gear<'a>(&'a Character);
+
+impl<'a> fmt::Display for Gear<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let character = self.0;
+        match character.loadout {
+            Some(loadout) => write!(
+                f,
+                "**Load** {}/{} ({})",
+                character.load(),
+                loadout.limit(),
+                loadout.as_str()
+            )?,
+            None => write!(f, "**Load** {} (not chosen)", character.load())?,
+        }
+        write!(f, "\n**Items** ")?;
+        if character.items.is_empty() {
+            write!(f, "None")?;
+        } else {
+            for (index, item) in character.items.iter().enumerate() {
+                write!(f, "{}{}", if index > 0 { ", " } else { "" }, item)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The result of a character taking or clearing stress.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StressChange {
+    /// The amount of stress taken, or if negative, cleared.
+    pub amount: i32,
+    /// The character's stress after the change.
+    pub stress: usize,
+    /// Whether the character's stress overflowed, causing them to suffer trauma.
+    pub trauma: bool,
+}
+
+impl fmt::Display for StressChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.amount < 0 {
+            write!(f, "cleared {} stress", -self.amount)
+        } else {
+            write!(f, "took {} stress", self.amount)
+        }
+        .and(if self.trauma {
+            write!(
+                f,
+                " and **suffers trauma**! Stress is cleared (now {}/{}). Choose a trauma with `!trauma <condition>`",
+                self.stress, MAXIMUM_STRESS
+            )
+        } else {
+            write!(f, " (now {}/{})", self.stress, MAXIMUM_STRESS)
+        })
+    }
+}
+
+/// The result of a character marking experience.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct XpChange {
+    /// The track on which experience was marked.
+    pub track: XpTrack,
+    /// The amount of experience marked, which is zero if the track was already full.
+    pub amount: usize,
+    /// The experience on the track after the change.
+    pub xp: usize,
+}
+
+impl XpChange {
+    /// Whether the track is full, so that the character may advance.
+    pub fn is_full(&self) -> bool {
+        self.xp >= self.track.maximum()
+    }
+}
+
+impl fmt::Display for XpChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.amount == 0 {
+            write!(
+                f,
+                "the {} track is already full ({}/{}). ",
+                self.track.as_str(),
+                self.xp,
+                self.track.maximum()
+            )?;
+        } else {
+            write!(
+                f,
+                "marked {} {} XP (now {}/{})",
+                self.amount,
+                self.track.as_str(),
+                self.xp,
+                self.track.maximum()
+            )?;
+            if !self.is_full() {
+                return Ok(());
+            }
+            write!(f, ". The {} track is **full**! ", self.track.as_str())?;
+        }
+        match self.track {
+            XpTrack::Playbook => write!(f, "Advance any action rating with `!advance <action>`"),
+            _ => write!(
+                f,
+                "Advance one of its action ratings with `!advance <action>`"
+            ),
+        }
+    }
+}
+
+/// Renders a rating as a row of filled and empty dots, as on a paper character sheet.
+struct Dots(usize);
+
+impl fmt::Display for Dots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for index in 0..MAXIMUM_ACTION_RATING {
+            write!(f, "{}", if index < self.0 { "●" } else { "○" })?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct AttributeRating {
     pub rating: usize,
@@ -191,6 +950,36 @@ pub enum AttributeName {
 }
 
 impl AttributeName {
+    pub const ALL: [AttributeName; 3] = [
+        AttributeName::Insight,
+        AttributeName::Prowess,
+        AttributeName::Resolve,
+    ];
+
+    /// The actions whose ratings contribute to this attribute.
+    pub fn actions(&self) -> [ActionName; 4] {
+        match self {
+            AttributeName::Insight => [
+                ActionName::Doctor,
+                ActionName::Hack,
+                ActionName::Rig,
+                ActionName::Study,
+            ],
+            AttributeName::Prowess => [
+                ActionName::Helm,
+                ActionName::Scramble,
+                ActionName::Scrap,
+                ActionName::Skulk,
+            ],
+            AttributeName::Resolve => [
+                ActionName::Attune,
+                ActionName::Command,
+                ActionName::Consort,
+                ActionName::Sway,
+            ],
+        }
+    }
+
     pub fn parse(string: &str) -> Option<AttributeName> {
         match string.to_lowercase().as_ref() {
             "insight" => Some(AttributeName::Insight),
@@ -209,6 +998,70 @@ impl AttributeName {
     }
 }
 
+/// A track on which a character marks experience.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum XpTrack {
+    Playbook,
+    Insight,
+    Prowess,
+    Resolve,
+}
+
+impl XpTrack {
+    pub const ALL: [XpTrack; 4] = [
+        XpTrack::Playbook,
+        XpTrack::Insight,
+        XpTrack::Prowess,
+        XpTrack::Resolve,
+    ];
+
+    pub fn parse(string: &str) -> Option<XpTrack> {
+        match string.to_lowercase().as_ref() {
+            "playbook" => Some(XpTrack::Playbook),
+            "insight" => Some(XpTrack::Insight),
+            "prowess" => Some(XpTrack::Prowess),
+            "resolve" => Some(XpTrack::Resolve),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            XpTrack::Playbook => "Playbook",
+            XpTrack::Insight => "Insight",
+            XpTrack::Prowess => "Prowess",
+            XpTrack::Resolve => "Resolve",
+        }
+    }
+
+    /// The experience needed to fill this track.
+    pub fn maximum(&self) -> usize {
+        match self {
+            XpTrack::Playbook => 8,
+            _ => 6,
+        }
+    }
+
+    /// The track on which experience is marked for an attribute's actions.
+    pub fn for_attribute(attribute: AttributeName) -> XpTrack {
+        match attribute {
+            AttributeName::Insight => XpTrack::Insight,
+            AttributeName::Prowess => XpTrack::Prowess,
+            AttributeName::Resolve => XpTrack::Resolve,
+        }
+    }
+
+    /// The name of the column in the `characters` table that stores the experience on this track.
+    fn column_name(&self) -> &str {
+        match self {
+            XpTrack::Playbook => "playbook_xp",
+            XpTrack::Insight => "insight_xp",
+            XpTrack::Prowess => "prowess_xp",
+            XpTrack::Resolve => "resolve_xp",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ActionName {
     Attune,
@@ -260,4 +1113,37 @@ impl ActionName {
             ActionName::Sway => "Sway",
         }
     }
+
+    /// The attribute to which this action's rating contributes.
+    pub fn attribute(&self) -> AttributeName {
+        match self {
+            ActionName::Doctor | ActionName::Hack | ActionName::Rig | ActionName::Study => {
+                AttributeName::Insight
+            }
+            ActionName::Helm | ActionName::Scramble | ActionName::Scrap | ActionName::Skulk => {
+                AttributeName::Prowess
+            }
+            ActionName::Attune | ActionName::Command | ActionName::Consort | ActionName::Sway => {
+                AttributeName::Resolve
+            }
+        }
+    }
+
+    /// The name of the column in the `characters` table that stores this action's rating.
+    fn column_name(&self) -> &str {
+        match self {
+            ActionName::Attune => "attune",
+            ActionName::Command => "command",
+            ActionName::Consort => "consort",
+            ActionName::Doctor => "doctor",
+            ActionName::Hack => "hack",
+            ActionName::Helm => "helm",
+            ActionName::Rig => "rig",
+            ActionName::Scramble => "scramble",
+            ActionName::Scrap => "scrap",
+            ActionName::Skulk => "skulk",
+            ActionName::Study => "study",
+            ActionName::Sway => "sway",
+        }
+    }
 }
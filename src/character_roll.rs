@@ -1,50 +1,310 @@
-use crate::character::{AttributeName, Character, ActionName};
-use crate::roll::Roll;
+use crate::character::{AttributeName, Character, ActionName, XpTrack, MAXIMUM_STRESS};
+use crate::roll;
+use crate::roll::{Effect, Position, Roll, RollResult};
+use crate::ship::{ResourceName, Ship, SystemName};
 use regex::Regex;
+use serenity::model::id::UserId;
 use std::fmt;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct CharacterRoll {
     pub check: Check,
+    /// The name of the character making the roll, if not the user's active character.
+    pub character: Option<String>,
 }
 
 impl CharacterRoll {
     pub fn parse(string: &str) -> Option<CharacterRoll> {
+        lazy_static! {
+            static ref CHARACTER_REGEX: Regex =
+                Regex::new(r#"(?i)^(.+?) +as +(?:"([^"]+)"|([^\s"]+))$"#).unwrap();
+        }
+
+        let (string, character) = match CHARACTER_REGEX.captures(string) {
+            Some(captures) => (
+                captures.get(1)?.as_str(),
+                captures
+                    .get(2)
+                    .or_else(|| captures.get(3))
+                    .map(|m| m.as_str().to_owned()),
+            ),
+            None => (string, None),
+        };
         let check = Check::parse(string)?;
-        Some(CharacterRoll { check })
+        Some(CharacterRoll { check, character })
     }
 
-    pub fn to_roll(&self, character: &Character) -> Option<Roll> {
-        let rating = match self.check {
-            Check::Attribute(name) => character.attribute(name)?.rating,
-            Check::Action(name, bonus) => character.action(name)?.rating + bonus,
-        };
-        Some(Roll::new(rating).unwrap())
+    /// Build the roll for this check from a character's ratings, or for a ship system check from
+    /// the crew's ship, applying any modifiers to the dice pool.
+    pub fn to_roll(&self, character: &Character, ship: Option<&Ship>) -> Result<Roll, Error> {
+        if let Check::Action(_, options) = &self.check {
+            if character.has_harm(4) {
+                return Err(Error::FatalHarm);
+            }
+            if character.has_harm(3) && options.assisted_by.is_none() {
+                return Err(Error::SevereHarm);
+            }
+            if options.push && character.stress() + PUSH_STRESS > MAXIMUM_STRESS {
+                return Err(Error::InsufficientStress);
+            }
+            if options.gambit
+                && ship.ok_or(Error::ShipNotFound)?.resource(ResourceName::Gambits) == 0
+            {
+                return Err(Error::NoGambits);
+            }
+        }
+        self.to_unchecked_roll(character, ship)
+    }
+
+    /// Build the roll for this check without checking whether the character is able to make it,
+    /// such as when working out the odds of the roll.
+    pub fn to_unchecked_roll(
+        &self,
+        character: &Character,
+        ship: Option<&Ship>,
+    ) -> Result<Roll, Error> {
+        let rating = match &self.check {
+            Check::Attribute(name) => character.attribute(*name).map(|rating| rating.rating),
+            Check::Action(name, _) => character.action(*name).map(|rating| rating.rating),
+            Check::System(name) => Some(ship.ok_or(Error::ShipNotFound)?.system(*name)),
+        }
+        .ok_or(Error::RatingNotSet)?;
+        let modifiers = self.modifiers(character);
+        let dice = modifiers
+            .iter()
+            .fold(rating as i32, |dice, modifier| dice + modifier.dice());
+        let roll = Roll::new(dice.max(0) as usize).map_err(Error::RollInvalid)?;
+        Ok(match &self.check {
+            Check::Attribute(_) | Check::System(_) => roll,
+            Check::Action(_, options) => {
+                let effect = if modifiers.contains(&Modifier::LesserHarm) {
+                    options.effect.map(|effect| effect.reduced())
+                } else {
+                    options.effect
+                };
+                roll.with_position(options.position).with_effect(effect)
+            }
+        })
+    }
+
+    /// The modifiers that apply to the dice pool of this check for a character.
+    pub fn modifiers(&self, character: &Character) -> Vec<Modifier> {
+        let mut modifiers = Vec::new();
+        if let Check::Action(_, options) = &self.check {
+            if options.bonus > 0 {
+                modifiers.push(Modifier::BonusDice(options.bonus));
+            }
+            if options.push {
+                modifiers.push(Modifier::Push);
+            }
+            if let Some(user_id) = options.assisted_by {
+                modifiers.push(Modifier::Assist(user_id));
+            }
+            if options.bargain.is_some() {
+                modifiers.push(Modifier::Bargain);
+            }
+            if options.gambit {
+                modifiers.push(Modifier::Gambit);
+            }
+        }
+        // Harm only hinders the character, not ship system checks made by the whole crew
+        if let Check::Attribute(_) | Check::Action(_, _) = &self.check {
+            if character.has_harm(2) {
+                modifiers.push(Modifier::ModerateHarm);
+            }
+        }
+        // Lesser harm reduces the effect of an action, if the GM has set one that can be reduced
+        if let Check::Action(
+            _,
+            ActionOptions {
+                effect: Some(effect),
+                ..
+            },
+        ) = &self.check
+        {
+            if character.has_harm(1) && effect.reduced() != *effect {
+                modifiers.push(Modifier::LesserHarm);
+            }
+        }
+        modifiers
+    }
+
+    /// The stress that a character takes, or if negative clears, as a result of this roll.
+    ///
+    /// Resistance rolls cost stress depending on the result, while pushing yourself on an action
+    /// roll costs a fixed amount of stress.
+    pub fn stress(&self, result: &RollResult) -> Option<i32> {
+        match &self.check {
+            Check::Attribute(_) => Some(result.resistance_stress()),
+            Check::Action(_, options) if options.push => Some(PUSH_STRESS as i32),
+            Check::Action(_, _) | Check::System(_) => None,
+        }
+    }
+
+    /// The XP track on which a character marks experience as a result of this roll, which is the
+    /// track for the action's attribute on a desperate action roll.
+    pub fn xp(&self) -> Option<XpTrack> {
+        match &self.check {
+            Check::Action(action, options) if options.position == Some(Position::Desperate) => {
+                Some(XpTrack::for_attribute(action.attribute()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The gambits that the crew earn, or if negative spend, as a result of this roll.
+    ///
+    /// Spending a gambit on an action roll costs one gambit, while a risky action roll that yields
+    /// a 6 without spending a gambit earns one.
+    pub fn gambits(&self, result: &RollResult) -> i32 {
+        match &self.check {
+            Check::Action(_, options) if options.gambit => -1,
+            Check::Action(_, _) if result.earns_gambit() => 1,
+            _ => 0,
+        }
     }
 }
 
-#[derive(Debug)]
+/// The stress taken by a character when they push themselves.
+pub const PUSH_STRESS: usize = 2;
+
+/// A modifier to a character roll, explaining how and why the dice pool changed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Modifier {
+    Assist(UserId),
+    Bargain,
+    BonusDice(usize),
+    Gambit,
+    LesserHarm,
+    ModerateHarm,
+    Push,
+}
+
+impl Modifier {
+    /// The number of dice added to, or if negative, removed from the dice pool.
+    pub fn dice(&self) -> i32 {
+        match self {
+            Modifier::Assist(_) => 1,
+            Modifier::Bargain => 1,
+            Modifier::BonusDice(bonus) => *bonus as i32,
+            Modifier::Gambit => 1,
+            Modifier::LesserHarm => 0,
+            Modifier::ModerateHarm => -1,
+            Modifier::Push => 1,
+        }
+    }
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Modifier::Assist(user_id) => write!(f, "+1d from <@{}>'s assistance", user_id),
+            Modifier::Bargain => write!(f, "+1d from a devil's bargain"),
+            Modifier::BonusDice(bonus) => write!(f, "+{}d bonus dice", bonus),
+            Modifier::Gambit => write!(f, "+1d from spending a gambit"),
+            Modifier::LesserHarm => write!(f, "less effect from level 1 harm"),
+            Modifier::ModerateHarm => write!(f, "-1d from level 2 harm"),
+            Modifier::Push => write!(f, "+1d from pushing yourself"),
+        }
+    }
+}
+
+/// Represents an error that prevents a character from making a roll.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    FatalHarm,
+    InsufficientStress,
+    NoGambits,
+    // TODO Remove this, attributes are not optional
+    RatingNotSet,
+    RollInvalid(roll::Error),
+    SevereHarm,
+    ShipNotFound,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::FatalHarm => write!(f, "Your character is suffering fatal harm, and can't act."),
+            Error::InsufficientStress => write!(f, "Your character doesn't have enough stress left to push themselves. Pushing yourself costs {} stress, and you can't take more than {}.", PUSH_STRESS, MAXIMUM_STRESS),
+            Error::NoGambits => write!(f, "Your crew has no gambits to spend. Gambits are earned by rolling a 6 on a risky action roll."),
+            Error::RatingNotSet => {
+                write!(f, "Couldn't find required attribute ratings for character.")
+            }
+            Error::RollInvalid(error) => error.fmt(f),
+            Error::SevereHarm => write!(f, "Your character is suffering level 3 harm or worse, and can't act without help. Ask another character to assist you with `!assist`."),
+            Error::ShipNotFound => write!(f, "Couldn't find a ship for this channel. Try typing `!ship create` to create one."),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub enum Check {
     Attribute(AttributeName),
-    Action(ActionName, usize),
+    Action(ActionName, ActionOptions),
+    System(SystemName),
+}
+
+/// Options that modify the dice pool of an action roll.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ActionOptions {
+    /// The number of bonus dice added to the roll.
+    pub bonus: usize,
+    /// Whether the character pushes themselves, taking stress for an extra die.
+    pub push: bool,
+    /// The position of the roll, if the GM has set one.
+    pub position: Option<Position>,
+    /// The effect of the roll, if the GM has set one.
+    pub effect: Option<Effect>,
+    /// The user whose character is assisting the roll, if any.
+    pub assisted_by: Option<UserId>,
+    /// The devil's bargain accepted for an extra die, if any.
+    pub bargain: Option<String>,
+    /// Whether the crew spends a gambit for an extra die.
+    pub gambit: bool,
 }
 
 impl Check {
     pub fn parse(string: &str) -> Option<Check> {
         lazy_static! {
-            static ref RE: Regex =
-                Regex::new(r"^(.*?)(?: with (\d+) bonus dice)?$").unwrap();
+            static ref ACTION_REGEX: Regex = Regex::new(r"^(\w+)(.*)$").unwrap();
+            static ref SYSTEM_REGEX: Regex = Regex::new(r"(?i)^ship +(\w+)$").unwrap();
+            static ref OPTION_REGEX: Regex = Regex::new(
+                r#"(?i)^ +(?:with (\d+) bonus dic?e|(push)|(controlled|risky|desperate)|(limited|standard|great)|\+bargain +"([^"]+)"|(\+gambit))"#
+            )
+            .unwrap();
         }
 
         AttributeName::parse(string)
             .map(Check::Attribute)
-            .or_else(|| 
-                RE.captures(string).and_then(|captures| {
-                    let action = ActionName::parse(captures.get(1)?.as_str())?;
-                    let bonus = captures.get(2)?.as_str().parse::<usize>().ok()?;
-                    Some(Check::Action(action, bonus))
-                })
-            )
+            .or_else(|| {
+                let captures = SYSTEM_REGEX.captures(string)?;
+                SystemName::parse(captures.get(1)?.as_str()).map(Check::System)
+            })
+            .or_else(|| {
+                let captures = ACTION_REGEX.captures(string)?;
+                let action = ActionName::parse(captures.get(1)?.as_str())?;
+                let mut options = ActionOptions::default();
+                let mut rest = captures.get(2)?.as_str();
+                while !rest.is_empty() {
+                    let captures = OPTION_REGEX.captures(rest)?;
+                    if let Some(bonus) = captures.get(1) {
+                        options.bonus = bonus.as_str().parse::<usize>().ok()?;
+                    } else if captures.get(2).is_some() {
+                        options.push = true;
+                    } else if let Some(position) = captures.get(3) {
+                        options.position = Position::parse(position.as_str());
+                    } else if let Some(effect) = captures.get(4) {
+                        options.effect = Effect::parse(effect.as_str());
+                    } else if let Some(bargain) = captures.get(5) {
+                        options.bargain = Some(bargain.as_str().to_owned());
+                    } else if captures.get(6).is_some() {
+                        options.gambit = true;
+                    }
+                    rest = &rest[captures.get(0)?.end()..];
+                }
+                Some(Check::Action(action, options))
+            })
     }
 }
 
@@ -53,6 +313,7 @@ impl fmt::Display for Check {
         match self {
             Check::Attribute(name) => name.as_str().fmt(f),
             Check::Action(name, _) => name.as_str().fmt(f),
+            Check::System(name) => write!(f, "Ship {}", name.as_str()),
         }
     }
 }
@@ -0,0 +1,135 @@
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, OptionalExtension, Row};
+use serenity::model::id::ChannelId;
+use std::convert::TryInto;
+use std::fmt;
+
+/// The most segments a clock may have.
+pub const MAXIMUM_CLOCK_SEGMENTS: usize = 12;
+
+/// A progress clock, such as "Alarm", which is ticked as a channel's crew make progress or
+/// trouble grows.
+///
+/// Clocks are named per channel, and names are matched case-insensitively. A clock may belong to a
+/// faction, tracking that faction's own projects.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Clock {
+    pub name: String,
+    pub segments: usize,
+    pub ticks: usize,
+    pub faction: Option<String>,
+}
+
+impl Clock {
+    /// Get a clock in a channel by name.
+    pub fn get(
+        connection: &Connection,
+        channel_id: ChannelId,
+        name: &str,
+    ) -> RusqliteResult<Option<Clock>> {
+        connection
+            .query_row(
+                "SELECT name, segments, ticks, faction FROM clocks WHERE channel_id = $1 AND name = $2",
+                &[&channel_id.to_string(), name],
+                Clock::from_row,
+            )
+            .optional()
+    }
+
+    /// Get all of the clocks in a channel, in the order they were started.
+    pub fn get_all(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<Vec<Clock>> {
+        let mut statement = connection.prepare(
+            "SELECT name, segments, ticks, faction \
+             FROM clocks \
+             WHERE channel_id = $1 \
+             ORDER BY rowid",
+        )?;
+        let clocks = statement
+            .query_map(&[&channel_id.to_string()], Clock::from_row)?
+            .collect();
+        clocks
+    }
+
+    /// Start a clock in a channel, unless a clock with the same name already exists.
+    ///
+    /// Returns the number of clocks created.
+    pub fn create(
+        connection: &Connection,
+        channel_id: ChannelId,
+        clock: &Clock,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &channel_id.to_string(),
+            &clock.name,
+            &(clock.segments as i32),
+            &(clock.ticks as i32),
+            &clock.faction,
+        ];
+        connection.execute(
+            "INSERT OR IGNORE INTO clocks (channel_id, name, segments, ticks, faction) VALUES ($1, $2, $3, $4, $5)",
+            params,
+        )
+    }
+
+    /// Store the number of ticks on a clock.
+    pub fn set_ticks(
+        connection: &Connection,
+        channel_id: ChannelId,
+        clock: &Clock,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&(clock.ticks as i32), &channel_id.to_string(), &clock.name];
+        connection.execute(
+            "UPDATE clocks SET ticks = $1 WHERE channel_id = $2 AND name = $3",
+            params,
+        )
+    }
+
+    /// Delete a clock in a channel by name.
+    ///
+    /// Returns the number of clocks deleted.
+    pub fn delete(
+        connection: &Connection,
+        channel_id: ChannelId,
+        name: &str,
+    ) -> RusqliteResult<usize> {
+        connection.execute(
+            "DELETE FROM clocks WHERE channel_id = $1 AND name = $2",
+            &[&channel_id.to_string(), name],
+        )
+    }
+
+    /// The clock after ticking a number of segments, stopping once it is full.
+    pub fn tick(&self, ticks: usize) -> Clock {
+        Clock {
+            ticks: (self.ticks + ticks).min(self.segments),
+            ..self.clone()
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.ticks >= self.segments
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Clock> {
+        Ok(Clock {
+            name: row.get("name")?,
+            segments: row.get::<_, i32>("segments")?.try_into().unwrap_or(0),
+            ticks: row.get::<_, i32>("ticks")?.try_into().unwrap_or(0),
+            faction: row.get("faction")?,
+        })
+    }
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "**{}** ", self.name)?;
+        if let Some(faction) = self.faction.as_ref() {
+            write!(f, "({}) ", faction)?;
+        }
+        for segment in 0..self.segments {
+            write!(f, "{}", if segment < self.ticks { "🟥" } else { "⬜" })?;
+        }
+        write!(f, " {}/{}", self.ticks, self.segments)
+    }
+}
@@ -1,11 +1,27 @@
-use crate::character_roll::CharacterRoll;
+use crate::character::{ActionName, XpTrack, MAXIMUM_ACTION_RATING};
+use crate::character_roll::{CharacterRoll, Check};
+use crate::clock::{Clock, MAXIMUM_CLOCK_SEGMENTS};
+use crate::dice_expression;
+use crate::dice_expression::DiceExpression;
+use crate::downtime::DowntimeActivity;
+use crate::engagement::Engagement;
+use crate::entanglement::Entanglement;
 use crate::error;
+use crate::faction::{Hold, MAXIMUM_TIER};
+use crate::harm::MAXIMUM_HARM_LEVEL;
+use crate::heat::JobEnd;
 use crate::intent_parser::parse_intent_result;
+use crate::item::Loadout;
+use crate::playbook::{Playbook, PlaybookList};
 use crate::response::Response;
 use crate::roll;
-use crate::roll::Roll;
+use crate::roll::{Roll, RollKind};
 use crate::roll::Error as RollError;
+use crate::roll_history::RollFilter;
+use crate::ship::{ResourceName, SystemName, MAXIMUM_SYSTEM_RATING};
+use crate::trauma::Trauma;
 use regex::Regex;
+use serenity::model::id::{ChannelId, UserId};
 use snips_nlu_lib::SnipsNluEngine;
 use snips_nlu_ontology::IntentParserResult;
 use std::fmt;
@@ -13,17 +29,129 @@ use symspell::{SymSpell, UnicodeStringStrategy};
 
 #[derive(Debug)]
 pub enum Command {
+    AddFaction(String, usize, Hold),
+    AddItem(String, usize),
+    AddShipUpgrade(String),
+    Advance(ActionName),
+    AdjustFactionStatus(String, i32),
+    Assist(UserId),
+    CharacterOdds(crate::character_roll::CharacterRoll, bool),
     CharacterRoll(crate::character_roll::CharacterRoll),
+    ChooseTrauma(Trauma),
+    ClearHarm,
+    CreateCharacter(Option<String>, Option<&'static Playbook>),
+    CreateClock(Clock),
+    CreateShip,
+    DeleteClock(String),
+    DeleteFaction(String),
+    Downtime(DowntimeActivity),
+    EndJob(JobEnd),
+    EndSession,
+    Engagement(Engagement),
+    Entanglement(Entanglement),
+    HealHarm,
     Help,
+    JoinGuildCampaign,
+    LinkCampaign(ChannelId),
+    ListAbilities(Option<&'static Playbook>),
+    ListBargains,
+    ListCharacters,
+    ListClocks,
+    ListFactions,
+    MarkXp(XpTrack, usize),
+    Odds(crate::roll::Roll),
+    RemoveItem(String),
+    RemoveShipUpgrade(String),
+    ResolveBargains,
+    ResolveGroupAction,
     Roll(crate::roll::Roll),
+    RollExpression(DiceExpression),
+    SetActionRating(ActionName, usize),
+    SetCrewTier(usize),
+    SetFactionHold(String, Hold),
+    SetFactionTier(String, usize),
+    SetLoadout(Loadout),
+    SetShipResource(ResourceName, usize),
+    SetShipSystem(SystemName, usize),
+    ShowCampaign,
+    ShowCharacter,
+    ShowHeat,
+    ShowHistory(RollFilter),
+    ShowLoad,
+    ShowShip,
+    ShowStats(Option<UserId>, bool),
+    StartDowntime,
+    StartGroupAction(ActionName),
+    StartSession,
+    TakeHarm(usize, String),
+    TickClock(String, usize),
+    UnlinkCampaign,
+    UseCharacter(String),
+    UseItem(String),
 }
 
 impl Command {
     pub fn description(&self) -> &str {
         match self {
+            Command::AddFaction(_, _, _) => "add a faction",
+            Command::AddItem(_, _) => "add an item",
+            Command::AddShipUpgrade(_) => "add a ship upgrade",
+            Command::Advance(_) => "advance an action rating",
+            Command::AdjustFactionStatus(_, _) => "adjust a faction's status",
+            Command::Assist(_) => "assist another character",
+            Command::CharacterOdds(_, _) => "work out the odds of a character roll",
             Command::CharacterRoll(_) => "perform a character roll",
+            Command::ChooseTrauma(_) => "choose a trauma",
+            Command::ClearHarm => "clear harm",
+            Command::CreateCharacter(_, _) => "create a character",
+            Command::CreateClock(_) => "start a clock",
+            Command::CreateShip => "create a ship",
+            Command::DeleteClock(_) => "delete a clock",
+            Command::DeleteFaction(_) => "delete a faction",
+            Command::Downtime(_) => "take a downtime activity",
+            Command::EndJob(_) => "end a job",
+            Command::EndSession => "end a session",
+            Command::Engagement(_) => "perform an engagement roll",
+            Command::Entanglement(_) => "perform an entanglement roll",
+            Command::HealHarm => "heal harm",
             Command::Help => "ask for help",
+            Command::JoinGuildCampaign => "join the server's campaign",
+            Command::LinkCampaign(_) => "link a channel to a campaign",
+            Command::ListAbilities(_) => "list special abilities",
+            Command::ListBargains => "list devil's bargains",
+            Command::ListCharacters => "list your characters",
+            Command::ListClocks => "list clocks",
+            Command::ListFactions => "list factions",
+            Command::MarkXp(_, _) => "mark XP",
+            Command::Odds(_) => "work out the odds of a roll",
+            Command::RemoveItem(_) => "remove an item",
+            Command::RemoveShipUpgrade(_) => "remove a ship upgrade",
+            Command::ResolveBargains => "resolve devil's bargains",
+            Command::ResolveGroupAction => "resolve a group action",
             Command::Roll(_) => "perform a roll",
+            Command::RollExpression(_) => "roll a dice expression",
+            Command::SetActionRating(_, _) => "set an action rating",
+            Command::SetCrewTier(_) => "set the crew's tier",
+            Command::SetFactionHold(_, _) => "set a faction's hold",
+            Command::SetFactionTier(_, _) => "set a faction's tier",
+            Command::SetLoadout(_) => "choose a load",
+            Command::SetShipResource(_, _) => "set a ship resource",
+            Command::SetShipSystem(_, _) => "set a ship system rating",
+            Command::ShowCampaign => "show the channel's campaign",
+            Command::ShowCharacter => "show a character sheet",
+            Command::ShowHeat => "show the crew's heat",
+            Command::ShowHistory(_) => "show recent rolls",
+            Command::ShowLoad => "show your load",
+            Command::ShowShip => "show the ship sheet",
+            Command::ShowStats(_, _) => "show roll statistics",
+            Command::StartDowntime => "start a downtime phase",
+            Command::StartGroupAction(_) => "lead a group action",
+            Command::StartSession => "start a session",
+            Command::TakeHarm(_, _) => "take harm",
+            Command::TickClock(_, _) => "tick a clock",
+            Command::UnlinkCampaign => "unlink a channel from its campaign",
+            Command::UseCharacter(_) => "switch characters",
+            Command::UseItem(_) => "use an item",
         }
     }
 }
@@ -31,16 +159,52 @@ impl Command {
 #[derive(Debug)]
 pub enum Error {
     // Shorthand commands
+    AdvanceParserError,
+    AssistParserError,
+    CampaignCommandParserError,
+    CharacterCommandParserError,
     CharacterRollParserError,
+    DiceExpressionParserError(dice_expression::ParserError, String),
+    ClockCommandParserError,
+    DowntimeCommandParserError,
+    EngagementParserError,
+    EntanglementParserError,
+    FactionCommandParserError,
+    GroupActionParserError,
+    HarmCommandParserError,
+    HistoryCommandParserError,
+    ItemCommandParserError,
+    JobCommandParserError,
+    JobEndMissingExposure,
+    LoadCommandParserError,
+    OddsCommandParserError,
+    PlaybookParserError,
     RollParserError(roll::ParserError),
+    SessionCommandParserError,
+    ShipCommandParserError,
+    StatsCommandParserError,
+    TraumaParserError,
+    XpCommandParserError,
 
     // Natural language commands
+    ChooseTraumaMissingTrauma,
     IntentParserError(::failure::Error),
     NoIntent,
+    TakeHarmMissingDescription,
+    TakeHarmMissingLevel,
     RollDiceInvalid(RollError, usize),
     RollResistanceMissingAttribute,
     RollActionMissingAction,
+    SetActionRatingMissingAction,
+    SetActionRatingMissingRating,
     UnknownIntent(String),
+
+    // Shared
+    ActionRatingTooGreat(ActionName, usize),
+    ClockSegmentsInvalid(usize),
+    HarmLevelInvalid(usize),
+    TierTooGreat(usize),
+    ShipSystemRatingTooGreat(SystemName, usize),
 }
 
 impl Error {
@@ -60,12 +224,108 @@ impl Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Error::ActionRatingTooGreat(action, rating) => {
+                write!(f, "It looks like you're trying to set your {} rating to {}. Action ratings must be between 0 and {}.", action.as_str(), rating, MAXIMUM_ACTION_RATING)
+            }
+            Error::CampaignCommandParserError => {
+                write!(f, "It looks like you're trying to manage this channel's campaign, but the syntax is invalid. Try `!campaign link #channel`, `!campaign guild`, `!campaign unlink` or `!campaign`.")
+            }
+            Error::CharacterCommandParserError => {
+                write!(f, "It looks like you're trying to manage your character, but the syntax is invalid. Try `!char create pilot Rin`, `!char set hack 2`, `!char show`, `!char list` or `!char use Rin`.")
+            }
+            Error::AdvanceParserError => {
+                write!(f, "It looks like you're trying to advance an action rating, but I'm not sure which action you want to advance. Try `!advance hack`.")
+            }
+            Error::AssistParserError => {
+                write!(f, "It looks like you're trying to assist another character, but the syntax is invalid. Try `!assist @player`.")
+            }
+            Error::ClockCommandParserError => {
+                write!(f, "It looks like you're trying to manage a clock, but the syntax is invalid. Try `!clock new \"Alarm\" 6`, `!clock new \"Expand territory\" 8 for Guilds`, `!clock tick Alarm 2`, `!clock list` or `!clock delete Alarm`.")
+            }
+            Error::ClockSegmentsInvalid(segments) => {
+                write!(f, "It looks like you're trying to start a clock with {} segments. Clocks must have between 1 and {} segments.", segments, MAXIMUM_CLOCK_SEGMENTS)
+            }
+            Error::DowntimeCommandParserError => {
+                write!(f, "It looks like you're trying to take a downtime activity, but the syntax is invalid. Try `!downtime recover`, `!downtime vice`, `!downtime train insight` or `!downtime project \"Fix the drive\" 8 rig`.")
+            }
+            Error::FactionCommandParserError => {
+                write!(f, "It looks like you're trying to manage factions, but the syntax is invalid. Try `!faction add \"The Hegemony\" 5 strong`, `!faction status Guilds +1`, `!faction tier Guilds 4`, `!faction hold Guilds weak`, `!faction list` or `!faction delete Guilds`.")
+            }
+            Error::TierTooGreat(tier) => {
+                write!(f, "It looks like you're trying to set a tier of {}. Tiers must be between 0 and {}.", tier, MAXIMUM_TIER)
+            }
+            Error::GroupActionParserError => {
+                write!(f, "It looks like you're trying to lead a group action, but the syntax is invalid. Try `!group sway` to lead a group action, or `!group done` to resolve it.")
+            }
+            Error::EngagementParserError => {
+                write!(f, "It looks like you're trying to roll an engagement roll, but the syntax is invalid. Try `!engage`, `!engage bold`, or `!engage bold +2 -1` for a bold plan with two advantages and one complication.")
+            }
+            Error::EntanglementParserError => {
+                write!(f, "It looks like you're trying to roll an entanglement roll, but the syntax is invalid. Try `!entanglement`, or `!entanglement vs Hegemony` for an entanglement with a faction.")
+            }
+            Error::HarmCommandParserError => {
+                write!(f, "It looks like you're trying to manage your character's harm, but the syntax is invalid. Try `!harm take 2 \"Broken arm\"`, `!harm heal` or `!harm clear`.")
+            }
+            Error::HarmLevelInvalid(level) => {
+                write!(f, "It looks like you're trying to take level {} harm. Harm must be between level 1 and level {}.", level, MAXIMUM_HARM_LEVEL)
+            }
+            Error::HistoryCommandParserError => {
+                write!(f, "It looks like you're trying to show recent rolls, but the syntax is invalid. Try `!history`, `!history @player` or `!history hack`.")
+            }
+            Error::ItemCommandParserError => {
+                write!(f, "It looks like you're trying to manage your items, but the syntax is invalid. Try `!item add \"Spacesuit\" 1`, `!item remove Spacesuit`, `!use Spacesuit` or `!items`.")
+            }
+            Error::OddsCommandParserError => {
+                write!(f, "It looks like you're trying to work out the odds of a roll, but the syntax is invalid. Try `!odds 3d`, `!odds hack`, `!odds hack push +assist`, etc.")
+            }
+            Error::LoadCommandParserError => {
+                write!(f, "It looks like you're trying to choose your load, but the syntax is invalid. Try `!load light`, `!load normal` or `!load heavy`.")
+            }
+            Error::JobCommandParserError => {
+                write!(f, "It looks like you're trying to end a job, but the syntax is invalid. Try `!job end loud hostile killing`.")
+            }
+            Error::JobEndMissingExposure => {
+                write!(f, "It looks like you're trying to end a job. How much exposure did the job have? Try `!job end quiet`, `!job end contained`, `!job end loud` or `!job end wild`, adding `high-profile`, `hostile`, `war` or `killing` if they apply, e.g. `!job end loud hostile killing`.")
+            }
+            Error::PlaybookParserError => {
+                write!(f, "It looks like you're trying to choose a playbook, but I don't recognise that playbook. Try one of {}, e.g. `!char create pilot`.", PlaybookList)
+            }
+            Error::SessionCommandParserError => {
+                write!(f, "It looks like you're trying to manage a session, but the syntax is invalid. Try `!session start` or `!session end`.")
+            }
+            Error::StatsCommandParserError => {
+                write!(f, "It looks like you're trying to show roll statistics, but the syntax is invalid. Try `!stats`, `!stats @player`, `!stats session` or `!stats session @player`.")
+            }
+            Error::ShipCommandParserError => {
+                write!(f, "It looks like you're trying to manage your ship, but the syntax is invalid. Try `!ship create`, `!ship set engines 2`, `!ship set cred 4`, `!ship set tier 2`, `!ship upgrade add \"Cloaking device\"` or `!ship show`.")
+            }
+            Error::ShipSystemRatingTooGreat(system, rating) => {
+                write!(f, "It looks like you're trying to set your ship's {} rating to {}. System ratings must be between 0 and {}.", system.as_str().to_lowercase(), rating, MAXIMUM_SYSTEM_RATING)
+            }
+            Error::XpCommandParserError => {
+                write!(f, "It looks like you're trying to mark XP, but the syntax is invalid. Try `!xp insight`, `!xp playbook 2`, etc.")
+            }
+            Error::TakeHarmMissingDescription => {
+                write!(f, "It looks like you're trying to take harm, but I'm not sure what harm you suffered. Try \"Take level 2 harm broken arm\", etc.")
+            }
+            Error::TakeHarmMissingLevel => {
+                write!(f, "It looks like you're trying to take harm, but I'm not sure what level of harm you suffered. Try \"Take level 2 harm broken arm\", etc.")
+            }
             Error::CharacterRollParserError => {
                 write!(f, "It looks like you're trying to roll an action or resistance roll, but the syntax is invalid. Try typing `!help` for some examples.")
             }
+            Error::DiceExpressionParserError(error, expression) => {
+                write!(f, "It looks like you're trying to roll a dice expression, but the syntax is invalid. {}\n```\n{}\n{}^\n```Try `!roll 2d8 + 4`, `!roll 4d6kh3`, `!roll 1d20 + 5 with advantage`, etc.", error, expression, " ".repeat(error.position()))
+            }
             Error::RollParserError(error) => {
                 write!(f, "It looks like you're trying to some dice, but the syntax is invalid. {} Try typing `!help` for some examples.", error)
             }
+            Error::TraumaParserError => {
+                write!(f, "It looks like you're trying to choose a trauma, but I don't recognise that trauma. Try {}.", TraumaList)
+            }
+            Error::ChooseTraumaMissingTrauma => {
+                write!(f, "It looks like you're trying to choose a trauma, but I'm not sure which trauma you want. Try {}.", TraumaList)
+            }
             Error::RollDiceInvalid(error, rolls) => match error {
                 RollError::RollsTooGreat => {
                     write!(f, "It looks like you're trying to roll {} dice. That's too many dice! Try rolling 100 or fewer dice.", rolls)
@@ -77,6 +337,12 @@ impl fmt::Display for Error {
             Error::RollActionMissingAction => {
                 write!(f, "It looks like you're trying to roll an action check, but I'm not sure what action you want. Try \"Roll command\", \"Hacking roll\", etc.")
             }
+            Error::SetActionRatingMissingAction => {
+                write!(f, "It looks like you're trying to set an action rating, but I'm not sure which action you want to set. Try \"Set my hack rating to 2\", \"Set sway to 1\", etc.")
+            }
+            Error::SetActionRatingMissingRating => {
+                write!(f, "It looks like you're trying to set an action rating, but I'm not sure what rating you want. Try \"Set my hack rating to 2\", \"Set sway to 1\", etc.")
+            }
             Error::NoIntent => {
                 write!(f, "I'm not sure what you mean. Try asking again with a different or simpler phrasing. Try asking for help to see some examples.")
             }
@@ -90,13 +356,42 @@ impl fmt::Display for Error {
     }
 }
 
+/// Renders the list of trauma conditions as shorthand command examples.
+struct TraumaList;
+
+impl fmt::Display for TraumaList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, trauma) in Trauma::ALL.iter().enumerate() {
+            write!(
+                f,
+                "{}`!trauma {}`",
+                if index > 0 { ", " } else { "" },
+                trauma.as_str().to_lowercase()
+            )?;
+        }
+        Ok(())
+    }
+}
+
 type NaturalLanguageCommandResult =
     Option<Result<(Result<Command, Error>, IntentParserResult, Option<String>), Error>>;
 
 impl Command {
+    /// Whether only server administrators may use the command.
+    pub fn is_admin(&self) -> bool {
+        match self {
+            Command::ResolveBargains => true,
+            _ => false,
+        }
+    }
+
     pub fn is_private(&self) -> bool {
         match self {
-            Command::Help | Command::Roll(_) => true,
+            Command::Engagement(_)
+            | Command::Help
+            | Command::Odds(_)
+            | Command::Roll(_)
+            | Command::RollExpression(_) => true,
             _ => false,
         }
     }
@@ -169,11 +464,205 @@ impl Command {
 
     fn parse_shorthand(command: &str) -> Option<Result<Command, Error>> {
         lazy_static! {
+            static ref ABILITIES_COMMAND_REGEX: Regex =
+                Regex::new(r"^!abilities(?: +(.*))?$").unwrap();
+            static ref ADVANCE_COMMAND_REGEX: Regex =
+                Regex::new(r"^!advance(?: +(.*))?$").unwrap();
+            static ref ASSIST_COMMAND_REGEX: Regex =
+                Regex::new(r"^!assist(?: +(.*))?$").unwrap();
+            static ref CAMPAIGN_COMMAND_REGEX: Regex =
+                Regex::new(r"^!campaign(?: +(.*))?$").unwrap();
+            static ref CHARACTER_COMMAND_REGEX: Regex =
+                Regex::new(r"^!(?:c|char|character)(?: +(.*))?$").unwrap();
+            static ref CLOCK_COMMAND_REGEX: Regex = Regex::new(r"^!clock(?: +(.*))?$").unwrap();
+            static ref DOWNTIME_COMMAND_REGEX: Regex =
+                Regex::new(r"^!downtime(?: +(.*))?$").unwrap();
+            static ref ENGAGEMENT_COMMAND_REGEX: Regex =
+                Regex::new(r"^!engage(?:ment)?(?: +(.*))?$").unwrap();
+            static ref ENTANGLEMENT_COMMAND_REGEX: Regex =
+                Regex::new(r"^!entanglements?(?: +(.*))?$").unwrap();
+            static ref FACTION_COMMAND_REGEX: Regex =
+                Regex::new(r"^!factions?(?: +(.*))?$").unwrap();
+            static ref FORTUNE_COMMAND_REGEX: Regex =
+                Regex::new(r"^!(fortune|gather) +(.*)$").unwrap();
+            static ref GROUP_ACTION_COMMAND_REGEX: Regex =
+                Regex::new(r"^!group(?: +(.*))?$").unwrap();
+            static ref HISTORY_COMMAND_REGEX: Regex =
+                Regex::new(r"^!history(?: +(.*))?$").unwrap();
+            static ref ITEM_COMMAND_REGEX: Regex = Regex::new(r"^!item(?: +(.*))?$").unwrap();
+            static ref JOB_COMMAND_REGEX: Regex = Regex::new(r"^!job(?: +(.*))?$").unwrap();
+            static ref LOAD_COMMAND_REGEX: Regex = Regex::new(r"^!load(?: +(.*))?$").unwrap();
+            static ref HARM_COMMAND_REGEX: Regex = Regex::new(r"^!harm(?: +(.*))?$").unwrap();
+            static ref ODDS_COMMAND_REGEX: Regex =
+                Regex::new(r"(?i)^!odds +(.+?)( +\+assist)?$").unwrap();
+            static ref SESSION_COMMAND_REGEX: Regex =
+                Regex::new(r"^!session(?: +(.*))?$").unwrap();
+            static ref STATS_COMMAND_REGEX: Regex = Regex::new(r"^!stats(?: +(.*))?$").unwrap();
+            static ref SHIP_COMMAND_REGEX: Regex = Regex::new(r"^!ship(?: +(.*))?$").unwrap();
             static ref ROLL_COMMAND_REGEX: Regex = Regex::new(r"^!(?:r|roll) +(.*)$").unwrap();
+            static ref TRAUMA_COMMAND_REGEX: Regex = Regex::new(r"^!trauma +(.*)$").unwrap();
+            static ref USE_COMMAND_REGEX: Regex =
+                Regex::new(r#"^!use +(?:"([^"]+)"|(.+))$"#).unwrap();
+            static ref XP_COMMAND_REGEX: Regex = Regex::new(r"^!xp(?: +(.*))?$").unwrap();
         }
 
         if command == "!help" {
             Some(Ok(Command::Help))
+        } else if command == "!heat" {
+            Some(Ok(Command::ShowHeat))
+        } else if command == "!items" {
+            Some(Ok(Command::ShowLoad))
+        } else if command == "!bargains" {
+            Some(Ok(Command::ListBargains))
+        } else if command == "!bargains clear" {
+            Some(Ok(Command::ResolveBargains))
+        } else if let Some(captures) = ABILITIES_COMMAND_REGEX.captures(&command) {
+            let playbook = captures.get(1).map_or("", |m| m.as_str()).trim();
+            Some(if playbook.is_empty() {
+                Ok(Command::ListAbilities(None))
+            } else {
+                Playbook::get(playbook)
+                    .map(|playbook| Command::ListAbilities(Some(playbook)))
+                    .ok_or(Error::PlaybookParserError)
+            })
+        } else if let Some(captures) = ADVANCE_COMMAND_REGEX.captures(&command) {
+            let action = captures.get(1).map_or("", |m| m.as_str()).trim();
+            Some(
+                ActionName::parse(action)
+                    .map(Command::Advance)
+                    .ok_or(Error::AdvanceParserError),
+            )
+        } else if let Some(captures) = ASSIST_COMMAND_REGEX.captures(&command) {
+            let user = captures.get(1).map_or("", |m| m.as_str());
+            Some(
+                Command::parse_user_mention(user)
+                    .map(Command::Assist)
+                    .ok_or(Error::AssistParserError),
+            )
+        } else if let Some(captures) = CAMPAIGN_COMMAND_REGEX.captures(&command) {
+            let campaign_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_campaign_command(campaign_command))
+        } else if let Some(captures) = CHARACTER_COMMAND_REGEX.captures(&command) {
+            let character_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_character_command(character_command))
+        } else if let Some(captures) = CLOCK_COMMAND_REGEX.captures(&command) {
+            let clock_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_clock_command(clock_command))
+        } else if let Some(captures) = DOWNTIME_COMMAND_REGEX.captures(&command) {
+            let downtime_command = captures.get(1).map_or("", |m| m.as_str()).trim();
+            Some(match downtime_command {
+                "start" => Ok(Command::StartDowntime),
+                activity => DowntimeActivity::parse(activity)
+                    .ok_or(Error::DowntimeCommandParserError)
+                    .and_then(Command::downtime),
+            })
+        } else if let Some(captures) = ENGAGEMENT_COMMAND_REGEX.captures(&command) {
+            let engagement = captures.get(1).map_or("", |m| m.as_str());
+            Some(
+                Engagement::parse(engagement)
+                    .map(Command::Engagement)
+                    .ok_or(Error::EngagementParserError),
+            )
+        } else if let Some(captures) = ENTANGLEMENT_COMMAND_REGEX.captures(&command) {
+            let entanglement = captures.get(1).map_or("", |m| m.as_str());
+            Some(
+                Entanglement::parse(entanglement)
+                    .map(Command::Entanglement)
+                    .ok_or(Error::EntanglementParserError),
+            )
+        } else if let Some(captures) = FACTION_COMMAND_REGEX.captures(&command) {
+            let faction_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_faction_command(faction_command))
+        } else if let Some(captures) = FORTUNE_COMMAND_REGEX.captures(&command) {
+            let kind = match captures.get(1).map_or("", |m| m.as_str()) {
+                "fortune" => RollKind::Fortune,
+                _ => RollKind::GatherInformation,
+            };
+            let roll_command = captures.get(2).map_or("", |m| m.as_str());
+            Some(
+                Roll::parse(roll_command)
+                    .map(|roll| Command::Roll(roll.with_kind(kind)))
+                    .map_err(Error::RollParserError),
+            )
+        } else if let Some(captures) = GROUP_ACTION_COMMAND_REGEX.captures(&command) {
+            let group_action = captures.get(1).map_or("", |m| m.as_str()).trim();
+            Some(match group_action {
+                "done" => Ok(Command::ResolveGroupAction),
+                action => ActionName::parse(action)
+                    .map(Command::StartGroupAction)
+                    .ok_or(Error::GroupActionParserError),
+            })
+        } else if let Some(captures) = HARM_COMMAND_REGEX.captures(&command) {
+            let harm_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_harm_command(harm_command))
+        } else if let Some(captures) = HISTORY_COMMAND_REGEX.captures(&command) {
+            let filter = captures.get(1).map_or("", |m| m.as_str()).trim();
+            Some(if filter.is_empty() {
+                Ok(Command::ShowHistory(RollFilter::All))
+            } else {
+                Command::parse_user_mention(filter)
+                    .map(RollFilter::User)
+                    .or_else(|| Check::parse(filter).map(|check| RollFilter::Check(check.to_string())))
+                    .map(Command::ShowHistory)
+                    .ok_or(Error::HistoryCommandParserError)
+            })
+        } else if let Some(captures) = ITEM_COMMAND_REGEX.captures(&command) {
+            let item_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_item_command(item_command))
+        } else if let Some(captures) = JOB_COMMAND_REGEX.captures(&command) {
+            let job_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_job_command(job_command))
+        } else if let Some(captures) = LOAD_COMMAND_REGEX.captures(&command) {
+            let loadout = captures.get(1).map_or("", |m| m.as_str()).trim();
+            Some(if loadout.is_empty() {
+                Ok(Command::ShowLoad)
+            } else {
+                Loadout::parse(loadout)
+                    .map(Command::SetLoadout)
+                    .ok_or(Error::LoadCommandParserError)
+            })
+        } else if let Some(captures) = ODDS_COMMAND_REGEX.captures(&command) {
+            let odds_command = captures.get(1).map_or("", |m| m.as_str());
+            let assisted = captures.get(2).is_some();
+            Some(if assisted {
+                CharacterRoll::parse(odds_command)
+                    .map(|roll| Command::CharacterOdds(roll, true))
+                    .ok_or(Error::OddsCommandParserError)
+            } else {
+                Roll::parse(odds_command)
+                    .map(Command::Odds)
+                    .or_else(|_| {
+                        CharacterRoll::parse(odds_command)
+                            .map(|roll| Command::CharacterOdds(roll, false))
+                            .ok_or(Error::OddsCommandParserError)
+                    })
+            })
+        } else if let Some(captures) = SESSION_COMMAND_REGEX.captures(&command) {
+            let session_command = captures.get(1).map_or("", |m| m.as_str()).trim();
+            Some(match session_command {
+                "end" => Ok(Command::EndSession),
+                "start" => Ok(Command::StartSession),
+                _ => Err(Error::SessionCommandParserError),
+            })
+        } else if let Some(captures) = STATS_COMMAND_REGEX.captures(&command) {
+            let stats_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_stats_command(stats_command))
+        } else if let Some(captures) = SHIP_COMMAND_REGEX.captures(&command) {
+            let ship_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_ship_command(ship_command))
+        } else if let Some(captures) = TRAUMA_COMMAND_REGEX.captures(&command) {
+            let trauma = captures.get(1).map_or("", |m| m.as_str()).trim();
+            Some(
+                Trauma::parse(trauma)
+                    .map(Command::ChooseTrauma)
+                    .ok_or(Error::TraumaParserError),
+            )
+        } else if let Some(captures) = USE_COMMAND_REGEX.captures(&command) {
+            let name = captures.get(1).or_else(|| captures.get(2)).map_or("", |m| m.as_str());
+            Some(Ok(Command::UseItem(name.trim().to_owned())))
+        } else if let Some(captures) = XP_COMMAND_REGEX.captures(&command) {
+            let xp_command = captures.get(1).map_or("", |m| m.as_str());
+            Some(Command::parse_xp_command(xp_command))
         } else if let Some(captures) = ROLL_COMMAND_REGEX.captures(&command) {
             let roll_command = captures.get(1).map_or("", |m| m.as_str()).to_owned();
             Some(
@@ -184,12 +673,422 @@ impl Command {
                         CharacterRoll::parse(&roll_command)
                             .map(Command::CharacterRoll)
                             .ok_or(Error::CharacterRollParserError)
-                    }),
+                    })
+                    .or_else(|error| Command::parse_dice_expression(&roll_command, error)),
             )
         } else {
             None
         }
     }
+
+    /// Parse a dice expression, or if the command doesn't look like a dice expression, return the
+    /// error from parsing it as another kind of roll.
+    fn parse_dice_expression(command: &str, error: Error) -> Result<Command, Error> {
+        lazy_static! {
+            static ref DICE_EXPRESSION_REGEX: Regex = Regex::new(r"(?i)^(?:-|\d|d[\d%])").unwrap();
+        }
+
+        match DiceExpression::parse(command) {
+            Ok(expression) => Ok(Command::RollExpression(expression)),
+            Err(parser_error) if DICE_EXPRESSION_REGEX.is_match(command) => Err(
+                Error::DiceExpressionParserError(parser_error, command.to_owned()),
+            ),
+            Err(_) => Err(error),
+        }
+    }
+
+    fn parse_item_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref ADD_ITEM_REGEX: Regex =
+                Regex::new(r#"^add +(?:"([^"]+)"|(\S+))(?: +(\d+))?$"#).unwrap();
+            static ref REMOVE_ITEM_REGEX: Regex =
+                Regex::new(r#"^remove +(?:"([^"]+)"|(.+))$"#).unwrap();
+        }
+
+        let command = command.trim();
+        if command.is_empty() || command == "list" {
+            Ok(Command::ShowLoad)
+        } else if let Some(captures) = ADD_ITEM_REGEX.captures(command) {
+            let name = captures.get(1).or_else(|| captures.get(2)).map_or("", |m| m.as_str());
+            captures
+                .get(3)
+                .map_or(Some(1), |m| m.as_str().parse::<usize>().ok())
+                .map(|load| Command::AddItem(name.trim().to_owned(), load))
+                .ok_or(Error::ItemCommandParserError)
+        } else if let Some(captures) = REMOVE_ITEM_REGEX.captures(command) {
+            let name = captures.get(1).or_else(|| captures.get(2)).map_or("", |m| m.as_str());
+            Ok(Command::RemoveItem(name.trim().to_owned()))
+        } else {
+            Err(Error::ItemCommandParserError)
+        }
+    }
+
+    fn parse_xp_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref XP_REGEX: Regex = Regex::new(r"^(\w+)(?: +(\d+))?$").unwrap();
+        }
+
+        XP_REGEX
+            .captures(command.trim())
+            .and_then(|captures| {
+                let track = XpTrack::parse(captures.get(1)?.as_str())?;
+                let amount = captures
+                    .get(2)
+                    .map_or(Some(1), |m| m.as_str().parse::<usize>().ok())?;
+                Some(Command::MarkXp(track, amount))
+            })
+            .ok_or(Error::XpCommandParserError)
+    }
+
+    fn parse_campaign_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref LINK_CAMPAIGN_REGEX: Regex = Regex::new(r"^link +(.+)$").unwrap();
+        }
+
+        let command = command.trim();
+        if command.is_empty() || command == "show" {
+            Ok(Command::ShowCampaign)
+        } else if command == "guild" || command == "server" {
+            Ok(Command::JoinGuildCampaign)
+        } else if command == "unlink" {
+            Ok(Command::UnlinkCampaign)
+        } else {
+            LINK_CAMPAIGN_REGEX
+                .captures(command)
+                .and_then(|captures| Command::parse_channel_mention(captures.get(1)?.as_str()))
+                .map(Command::LinkCampaign)
+                .ok_or(Error::CampaignCommandParserError)
+        }
+    }
+
+    fn parse_character_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref CREATE_CHARACTER_REGEX: Regex =
+                Regex::new(r#"^create(?: +(\w+))?(?: +(?:"([^"]+)"|(\S+)))?$"#).unwrap();
+            static ref USE_CHARACTER_REGEX: Regex =
+                Regex::new(r#"^use +(?:"([^"]+)"|(.+))$"#).unwrap();
+            static ref SET_ACTION_RATING_REGEX: Regex = Regex::new(r"^set +(\w+) +(\d+)$").unwrap();
+        }
+
+        let command = command.trim();
+        if let Some(captures) = CREATE_CHARACTER_REGEX.captures(command) {
+            let name = captures
+                .get(2)
+                .or_else(|| captures.get(3))
+                .map(|m| m.as_str().trim().to_owned());
+            match (captures.get(1), name) {
+                (Some(playbook), name) => match Playbook::get(playbook.as_str()) {
+                    Some(playbook) => Ok(Command::CreateCharacter(name, Some(playbook))),
+                    None if name.is_none() => Ok(Command::CreateCharacter(
+                        Some(playbook.as_str().to_owned()),
+                        None,
+                    )),
+                    None => Err(Error::PlaybookParserError),
+                },
+                (None, name) => Ok(Command::CreateCharacter(name, None)),
+            }
+        } else if let Some(captures) = USE_CHARACTER_REGEX.captures(command) {
+            let name = captures.get(1).or_else(|| captures.get(2)).map_or("", |m| m.as_str());
+            Ok(Command::UseCharacter(name.trim().to_owned()))
+        } else if command == "list" {
+            Ok(Command::ListCharacters)
+        } else if command.is_empty() || command == "show" {
+            Ok(Command::ShowCharacter)
+        } else {
+            SET_ACTION_RATING_REGEX
+                .captures(command)
+                .and_then(|captures| {
+                    let action = ActionName::parse(captures.get(1)?.as_str())?;
+                    let rating = captures.get(2)?.as_str().parse::<usize>().ok()?;
+                    Some((action, rating))
+                })
+                .ok_or(Error::CharacterCommandParserError)
+                .and_then(|(action, rating)| Command::set_action_rating(action, rating))
+        }
+    }
+
+    fn parse_clock_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref NEW_CLOCK_REGEX: Regex =
+                Regex::new(r#"^new +(?:"([^"]+)"|(\S+)) +(\d+)(?: +for +(?:"([^"]+)"|(\S+)))?$"#)
+                    .unwrap();
+            static ref TICK_CLOCK_REGEX: Regex =
+                Regex::new(r#"^tick +(?:"([^"]+)"|(\S+))(?: +(\d+))?$"#).unwrap();
+            static ref DELETE_CLOCK_REGEX: Regex =
+                Regex::new(r#"^delete +(?:"([^"]+)"|(.+))$"#).unwrap();
+        }
+
+        let command = command.trim();
+        if command.is_empty() || command == "list" {
+            Ok(Command::ListClocks)
+        } else if let Some(captures) = NEW_CLOCK_REGEX.captures(command) {
+            let name = captures.get(1).or_else(|| captures.get(2)).map_or("", |m| m.as_str());
+            let faction = captures
+                .get(4)
+                .or_else(|| captures.get(5))
+                .map(|m| m.as_str().trim().to_owned());
+            captures
+                .get(3)
+                .and_then(|m| m.as_str().parse::<usize>().ok())
+                .ok_or(Error::ClockCommandParserError)
+                .and_then(|segments| {
+                    Command::create_clock(name.trim().to_owned(), segments, faction)
+                })
+        } else if let Some(captures) = TICK_CLOCK_REGEX.captures(command) {
+            let name = captures.get(1).or_else(|| captures.get(2)).map_or("", |m| m.as_str());
+            captures
+                .get(3)
+                .map_or(Some(1), |m| m.as_str().parse::<usize>().ok())
+                .map(|ticks| Command::TickClock(name.trim().to_owned(), ticks))
+                .ok_or(Error::ClockCommandParserError)
+        } else if let Some(captures) = DELETE_CLOCK_REGEX.captures(command) {
+            let name = captures.get(1).or_else(|| captures.get(2)).map_or("", |m| m.as_str());
+            Ok(Command::DeleteClock(name.trim().to_owned()))
+        } else {
+            Err(Error::ClockCommandParserError)
+        }
+    }
+
+    fn parse_faction_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref FACTION_COMMAND_REGEX: Regex = Regex::new(
+                r#"^(add|status|tier|hold|delete) +(?:"([^"]+)"|(\S+))(?: +(.+))?$"#
+            )
+            .unwrap();
+            static ref ADD_FACTION_REGEX: Regex =
+                Regex::new(r"^(\d+)(?: +(weak|strong))?$").unwrap();
+        }
+
+        let command = command.trim();
+        if command.is_empty() || command == "list" {
+            return Ok(Command::ListFactions);
+        }
+        let captures = FACTION_COMMAND_REGEX
+            .captures(command)
+            .ok_or(Error::FactionCommandParserError)?;
+        let name = captures
+            .get(2)
+            .or_else(|| captures.get(3))
+            .map_or("", |m| m.as_str())
+            .trim()
+            .to_owned();
+        let argument = captures.get(4).map(|m| m.as_str().trim());
+        match (captures.get(1).map_or("", |m| m.as_str()), argument) {
+            ("add", Some(argument)) => ADD_FACTION_REGEX
+                .captures(argument)
+                .and_then(|captures| {
+                    let tier = captures.get(1)?.as_str().parse::<usize>().ok()?;
+                    let hold = captures
+                        .get(2)
+                        .map_or(Some(Hold::Strong), |m| Hold::parse(m.as_str()))?;
+                    Some((tier, hold))
+                })
+                .ok_or(Error::FactionCommandParserError)
+                .and_then(|(tier, hold)| {
+                    Command::validate_tier(tier).map(|tier| Command::AddFaction(name, tier, hold))
+                }),
+            ("status", Some(argument)) => argument
+                .trim_start_matches('+')
+                .parse::<i32>()
+                .map(|status| Command::AdjustFactionStatus(name, status))
+                .map_err(|_| Error::FactionCommandParserError),
+            ("tier", Some(argument)) => argument
+                .parse::<usize>()
+                .map_err(|_| Error::FactionCommandParserError)
+                .and_then(Command::validate_tier)
+                .map(|tier| Command::SetFactionTier(name, tier)),
+            ("hold", Some(argument)) => Hold::parse(argument)
+                .map(|hold| Command::SetFactionHold(name, hold))
+                .ok_or(Error::FactionCommandParserError),
+            ("delete", None) => Ok(Command::DeleteFaction(name)),
+            _ => Err(Error::FactionCommandParserError),
+        }
+    }
+
+    fn parse_job_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref END_JOB_REGEX: Regex = Regex::new(r"^end(?: +(.*))?$").unwrap();
+        }
+
+        END_JOB_REGEX
+            .captures(command.trim())
+            .and_then(|captures| JobEnd::parse(captures.get(1).map_or("", |m| m.as_str())))
+            .ok_or(Error::JobCommandParserError)
+            .and_then(|job_end| job_end.ok_or(Error::JobEndMissingExposure))
+            .map(Command::EndJob)
+    }
+
+    fn parse_ship_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref SET_SHIP_REGEX: Regex = Regex::new(r"^set +(\w+) +(\d+)$").unwrap();
+            static ref SHIP_UPGRADE_REGEX: Regex =
+                Regex::new(r#"^upgrade +(add|remove) +(?:"([^"]+)"|(.+))$"#).unwrap();
+        }
+
+        let command = command.trim();
+        if command.is_empty() || command == "show" {
+            Ok(Command::ShowShip)
+        } else if command == "create" {
+            Ok(Command::CreateShip)
+        } else if let Some(captures) = SET_SHIP_REGEX.captures(command) {
+            let name = captures.get(1).map_or("", |m| m.as_str());
+            let amount = captures
+                .get(2)
+                .and_then(|m| m.as_str().parse::<usize>().ok())
+                .ok_or(Error::ShipCommandParserError)?;
+            if name == "tier" {
+                Command::validate_tier(amount).map(Command::SetCrewTier)
+            } else if let Some(system) = SystemName::parse(name) {
+                Command::set_ship_system(system, amount)
+            } else if let Some(resource) = ResourceName::parse(name) {
+                Ok(Command::SetShipResource(resource, amount))
+            } else {
+                Err(Error::ShipCommandParserError)
+            }
+        } else if let Some(captures) = SHIP_UPGRADE_REGEX.captures(command) {
+            let upgrade = captures
+                .get(2)
+                .or_else(|| captures.get(3))
+                .map_or("", |m| m.as_str())
+                .trim()
+                .to_owned();
+            match captures.get(1).map_or("", |m| m.as_str()) {
+                "add" => Ok(Command::AddShipUpgrade(upgrade)),
+                _ => Ok(Command::RemoveShipUpgrade(upgrade)),
+            }
+        } else {
+            Err(Error::ShipCommandParserError)
+        }
+    }
+
+    /// Parse the options of a stats command, `[@user] [session]`, in either order.
+    fn parse_stats_command(command: &str) -> Result<Command, Error> {
+        let mut user_id = None;
+        let mut session = false;
+        for word in command.split_whitespace() {
+            if word == "session" && !session {
+                session = true;
+            } else if let (None, Some(mention)) = (user_id, Command::parse_user_mention(word)) {
+                user_id = Some(mention);
+            } else {
+                return Err(Error::StatsCommandParserError);
+            }
+        }
+        Ok(Command::ShowStats(user_id, session))
+    }
+
+    /// Parse a Discord mention of a user, e.g. `<@123456789>`.
+    fn parse_user_mention(mention: &str) -> Option<UserId> {
+        lazy_static! {
+            static ref USER_MENTION_REGEX: Regex = Regex::new(r"^<@!?(\d+)>$").unwrap();
+        }
+
+        USER_MENTION_REGEX
+            .captures(mention.trim())
+            .and_then(|captures| captures.get(1)?.as_str().parse::<u64>().ok())
+            .map(UserId)
+    }
+
+    fn parse_channel_mention(mention: &str) -> Option<ChannelId> {
+        lazy_static! {
+            static ref CHANNEL_MENTION_REGEX: Regex = Regex::new(r"^<#(\d+)>$").unwrap();
+        }
+
+        CHANNEL_MENTION_REGEX
+            .captures(mention.trim())
+            .and_then(|captures| captures.get(1)?.as_str().parse::<u64>().ok())
+            .map(ChannelId)
+    }
+
+    fn parse_harm_command(command: &str) -> Result<Command, Error> {
+        lazy_static! {
+            static ref TAKE_HARM_REGEX: Regex =
+                Regex::new(r#"^take +(\d+) +(?:"([^"]+)"|(.+))$"#).unwrap();
+        }
+
+        match command.trim() {
+            "heal" => Ok(Command::HealHarm),
+            "clear" => Ok(Command::ClearHarm),
+            command => TAKE_HARM_REGEX
+                .captures(command)
+                .and_then(|captures| {
+                    let level = captures.get(1)?.as_str().parse::<usize>().ok()?;
+                    let description = captures.get(2).or_else(|| captures.get(3))?.as_str();
+                    Some((level, description.trim().to_owned()))
+                })
+                .ok_or(Error::HarmCommandParserError)
+                .and_then(|(level, description)| Command::take_harm(level, description)),
+        }
+    }
+
+    /// Create a command to take harm, validating that the level of harm is valid.
+    pub fn take_harm(level: usize, description: String) -> Result<Command, Error> {
+        if !(1..=MAXIMUM_HARM_LEVEL).contains(&level) {
+            Err(Error::HarmLevelInvalid(level))
+        } else {
+            Ok(Command::TakeHarm(level, description))
+        }
+    }
+
+    /// Create a command to take a downtime activity, validating that the number of segments for a
+    /// long-term project is valid.
+    pub fn downtime(activity: DowntimeActivity) -> Result<Command, Error> {
+        match activity {
+            DowntimeActivity::LongTermProject { segments, .. }
+                if !(1..=MAXIMUM_CLOCK_SEGMENTS).contains(&segments) =>
+            {
+                Err(Error::ClockSegmentsInvalid(segments))
+            }
+            activity => Ok(Command::Downtime(activity)),
+        }
+    }
+
+    /// Create a command to start a clock, validating that the number of segments is valid.
+    pub fn create_clock(
+        name: String,
+        segments: usize,
+        faction: Option<String>,
+    ) -> Result<Command, Error> {
+        if !(1..=MAXIMUM_CLOCK_SEGMENTS).contains(&segments) {
+            Err(Error::ClockSegmentsInvalid(segments))
+        } else {
+            Ok(Command::CreateClock(Clock {
+                name,
+                segments,
+                ticks: 0,
+                faction,
+            }))
+        }
+    }
+
+    /// Create a command to set a ship system rating, validating that the rating is no more than
+    /// the maximum allowed value.
+    pub fn set_ship_system(system: SystemName, rating: usize) -> Result<Command, Error> {
+        if rating > MAXIMUM_SYSTEM_RATING {
+            Err(Error::ShipSystemRatingTooGreat(system, rating))
+        } else {
+            Ok(Command::SetShipSystem(system, rating))
+        }
+    }
+
+    /// Validate that a faction's or crew's tier is no more than the maximum allowed value.
+    fn validate_tier(tier: usize) -> Result<usize, Error> {
+        if tier > MAXIMUM_TIER {
+            Err(Error::TierTooGreat(tier))
+        } else {
+            Ok(tier)
+        }
+    }
+
+    /// Create a command to set an action rating, validating that the rating is no more than the
+    /// maximum allowed value.
+    pub fn set_action_rating(action: ActionName, rating: usize) -> Result<Command, Error> {
+        if rating > MAXIMUM_ACTION_RATING {
+            Err(Error::ActionRatingTooGreat(action, rating))
+        } else {
+            Ok(Command::SetActionRating(action, rating))
+        }
+    }
 }
 
 pub enum CommandResult {
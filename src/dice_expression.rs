@@ -0,0 +1,637 @@
+use crate::roll::{RollMode, MAXIMUM_ROLLS, MAXIMUM_ROLLS_DISPLAY};
+use rand::distributions::{Distribution, Uniform};
+use rand::Rng;
+use regex::Regex;
+use std::error;
+use std::fmt;
+
+/// The maximum number of sides that a die in an expression may have.
+pub const MAXIMUM_SIDES: u32 = 1000;
+
+/// The maximum number of extra dice that exploding dice may add to a single term.
+pub const MAXIMUM_EXPLOSIONS: usize = 100;
+
+/// A general purpose dice expression, such as `1d20 + 5` or `4d6kh3`, for rolls that don't follow
+/// the Scum and Villainy rules.
+///
+/// An expression is a sum of terms, each of which is either a constant or a number of dice. Dice
+/// may explode, and may keep or drop their highest or lowest values. The whole expression may be
+/// rolled with advantage or disadvantage, in which case it's rolled twice and the better or worse
+/// total is kept.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiceExpression {
+    terms: Vec<(Sign, Term)>,
+    mode: RollMode,
+}
+
+/// Whether a term is added to or subtracted from the total of an expression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Sign {
+    Plus,
+    Minus,
+}
+
+/// A term of a dice expression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Term {
+    Constant(u32),
+    Dice(Dice),
+}
+
+/// A number of dice with the same number of sides, such as `3d8`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Dice {
+    count: usize,
+    sides: u32,
+    exploding: bool,
+    selection: Option<Selection>,
+}
+
+/// Which dice of a term count towards its total.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Selection {
+    KeepHighest(usize),
+    KeepLowest(usize),
+    DropHighest(usize),
+    DropLowest(usize),
+}
+
+/// Represents an error that might occur when parsing a dice expression from a String.
+///
+/// Each error records the position in the input where it occurred, counted in characters from
+/// zero.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParserError {
+    Expected(usize, &'static str),
+    NumberTooLarge(usize),
+    TooManyDice(usize),
+    InvalidSides(usize),
+    InvalidSelection(usize),
+    InvalidExplosion(usize),
+    DuplicateModifier(usize),
+}
+
+impl ParserError {
+    /// The position in the input where this error occurred.
+    pub fn position(&self) -> usize {
+        match self {
+            ParserError::Expected(position, _)
+            | ParserError::NumberTooLarge(position)
+            | ParserError::TooManyDice(position)
+            | ParserError::InvalidSides(position)
+            | ParserError::InvalidSelection(position)
+            | ParserError::InvalidExplosion(position)
+            | ParserError::DuplicateModifier(position) => *position,
+        }
+    }
+}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParserError::Expected(_, expected) => write!(f, "Expected {}", expected),
+            ParserError::NumberTooLarge(_) => write!(f, "That number is too large"),
+            ParserError::TooManyDice(_) => {
+                write!(f, "Must roll no more than {} dice", MAXIMUM_ROLLS)
+            }
+            ParserError::InvalidSides(_) => {
+                write!(f, "Dice must have from 1 to {} sides", MAXIMUM_SIDES)
+            }
+            ParserError::InvalidSelection(_) => {
+                write!(f, "Can't keep or drop more dice than are rolled")
+            }
+            ParserError::InvalidExplosion(_) => write!(f, "Dice with one side can't explode"),
+            ParserError::DuplicateModifier(_) => {
+                write!(f, "Dice may only explode once and keep or drop once")
+            }
+        }
+        .and(write!(f, " at character {}.", self.position() + 1))
+    }
+}
+
+impl error::Error for ParserError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
+
+/// A parser over the characters of a dice expression, tracking its position for error messages.
+struct Parser {
+    characters: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.characters.get(self.position).copied()
+    }
+
+    fn next_if(&mut self, predicate: impl Fn(char) -> bool) -> Option<char> {
+        let c = self.peek().filter(|c| predicate(*c))?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.next_if(char::is_whitespace).is_some() {}
+    }
+
+    fn number(&mut self) -> Result<Option<u32>, ParserError> {
+        let start = self.position;
+        while self.next_if(|c| c.is_ascii_digit()).is_some() {}
+        if self.position == start {
+            Ok(None)
+        } else {
+            self.characters[start..self.position]
+                .iter()
+                .collect::<String>()
+                .parse::<u32>()
+                .map(Some)
+                .map_err(|_| ParserError::NumberTooLarge(start))
+        }
+    }
+
+    fn expression(&mut self) -> Result<Vec<(Sign, Term)>, ParserError> {
+        let mut terms = Vec::new();
+        let mut dice = 0;
+        self.skip_whitespace();
+        let mut sign = match self.next_if(|c| c == '-') {
+            Some(_) => Sign::Minus,
+            None => Sign::Plus,
+        };
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            let term = self.term()?;
+            if let Term::Dice(Dice { count, .. }) = term {
+                dice += count;
+                if dice > MAXIMUM_ROLLS {
+                    return Err(ParserError::TooManyDice(start));
+                }
+            }
+            terms.push((sign, term));
+            self.skip_whitespace();
+            sign = match self.peek() {
+                Some('+') => Sign::Plus,
+                Some('-') => Sign::Minus,
+                Some(_) => return Err(ParserError::Expected(self.position, "`+` or `-`")),
+                None => return Ok(terms),
+            };
+            self.position += 1;
+        }
+    }
+
+    fn term(&mut self) -> Result<Term, ParserError> {
+        let start = self.position;
+        let count = self.number()?;
+        if self.next_if(|c| c == 'd' || c == 'D').is_none() {
+            return count
+                .map(Term::Constant)
+                .ok_or(ParserError::Expected(start, "a number or dice"));
+        }
+        let count = count.unwrap_or(1) as usize;
+        if count > MAXIMUM_ROLLS {
+            return Err(ParserError::TooManyDice(start));
+        }
+        let sides_start = self.position;
+        let sides = if self.next_if(|c| c == '%').is_some() {
+            100
+        } else {
+            self.number()?
+                .ok_or(ParserError::Expected(sides_start, "the number of sides"))?
+        };
+        if !(1..=MAXIMUM_SIDES).contains(&sides) {
+            return Err(ParserError::InvalidSides(sides_start));
+        }
+        let mut dice = Dice {
+            count,
+            sides,
+            exploding: false,
+            selection: None,
+        };
+        loop {
+            let modifier_start = self.position;
+            match self.peek() {
+                Some('!') => {
+                    self.position += 1;
+                    if dice.exploding {
+                        return Err(ParserError::DuplicateModifier(modifier_start));
+                    } else if sides == 1 {
+                        return Err(ParserError::InvalidExplosion(modifier_start));
+                    }
+                    dice.exploding = true;
+                }
+                Some(c) if "kKdD".contains(c) => {
+                    self.position += 1;
+                    if dice.selection.is_some() {
+                        return Err(ParserError::DuplicateModifier(modifier_start));
+                    }
+                    let keep = c == 'k' || c == 'K';
+                    let highest = match self.next_if(|c| "hHlL".contains(c)) {
+                        Some(c) => c == 'h' || c == 'H',
+                        None => keep,
+                    };
+                    let number = self.number()?.unwrap_or(1) as usize;
+                    if number > count {
+                        return Err(ParserError::InvalidSelection(modifier_start));
+                    }
+                    dice.selection = Some(match (keep, highest) {
+                        (true, true) => Selection::KeepHighest(number),
+                        (true, false) => Selection::KeepLowest(number),
+                        (false, true) => Selection::DropHighest(number),
+                        (false, false) => Selection::DropLowest(number),
+                    });
+                }
+                _ => return Ok(Term::Dice(dice)),
+            }
+        }
+    }
+}
+
+impl DiceExpression {
+    /// Parse a dice expression from a String, such as `2d8 + 4` or `1d20 + 5 with advantage`.
+    pub fn parse(string: &str) -> Result<DiceExpression, ParserError> {
+        lazy_static! {
+            static ref MODE_REGEX: Regex =
+                Regex::new(r"(?i)^(.*?)\s+(?:with\s+)?(advantage|disadvantage|adv|dis)\s*$")
+                    .unwrap();
+        }
+
+        let (expression, mode) = MODE_REGEX
+            .captures(string)
+            .and_then(|captures| {
+                let expression = captures.get(1)?.as_str();
+                let mode = RollMode::parse(captures.get(2)?.as_str())?;
+                Some((expression, mode))
+            })
+            .unwrap_or((string, RollMode::Normal));
+        let mut parser = Parser {
+            characters: expression.chars().collect(),
+            position: 0,
+        };
+        parser
+            .expression()
+            .map(|terms| DiceExpression { terms, mode })
+    }
+
+    /// Roll the expression, rolling it a second time if it has advantage or disadvantage.
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> DiceExpressionResult {
+        let first = self.evaluate(rng);
+        let rolled = match self.mode {
+            RollMode::Normal => (first, None),
+            mode => {
+                let second = self.evaluate(rng);
+                let keep_second = if mode == RollMode::Advantage {
+                    second.total > first.total
+                } else {
+                    second.total < first.total
+                };
+                if keep_second {
+                    (second, Some((mode, first)))
+                } else {
+                    (first, Some((mode, second)))
+                }
+            }
+        };
+        DiceExpressionResult {
+            kept: rolled.0,
+            discarded: rolled.1,
+        }
+    }
+
+    fn evaluate<R: Rng + ?Sized>(&self, rng: &mut R) -> Evaluation {
+        let terms: Vec<TermResult> = self
+            .terms
+            .iter()
+            .map(|(sign, term)| match term {
+                Term::Constant(value) => TermResult::Constant(*sign, *value),
+                Term::Dice(dice) => TermResult::Dice(*sign, dice.roll(rng)),
+            })
+            .collect();
+        let total = terms.iter().map(TermResult::total).sum();
+        Evaluation { terms, total }
+    }
+}
+
+impl fmt::Display for DiceExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (sign, term)) in self.terms.iter().enumerate() {
+            match (index, sign) {
+                (0, Sign::Plus) => Ok(()),
+                (0, Sign::Minus) => write!(f, "-"),
+                (_, Sign::Plus) => write!(f, " + "),
+                (_, Sign::Minus) => write!(f, " - "),
+            }?;
+            write!(f, "{}", term)?;
+        }
+        match self.mode {
+            RollMode::Normal => Ok(()),
+            mode => write!(f, " with {}", mode),
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Constant(value) => write!(f, "{}", value),
+            Term::Dice(dice) => {
+                write!(f, "{}d{}", dice.count, dice.sides)?;
+                if dice.exploding {
+                    write!(f, "!")?;
+                }
+                match dice.selection {
+                    Some(Selection::KeepHighest(number)) => write!(f, "kh{}", number),
+                    Some(Selection::KeepLowest(number)) => write!(f, "kl{}", number),
+                    Some(Selection::DropHighest(number)) => write!(f, "dh{}", number),
+                    Some(Selection::DropLowest(number)) => write!(f, "dl{}", number),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl Dice {
+    fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Die> {
+        let uniform = Uniform::new_inclusive(1, self.sides);
+        let mut dice = Vec::with_capacity(self.count);
+        let mut explosions = 0;
+        for _ in 0..self.count {
+            let mut value = uniform.sample(rng);
+            while self.exploding && value == self.sides && explosions < MAXIMUM_EXPLOSIONS {
+                dice.push(Die::new(value, true));
+                value = uniform.sample(rng);
+                explosions += 1;
+            }
+            dice.push(Die::new(value, false));
+        }
+        if let Some(selection) = self.selection {
+            let mut order: Vec<usize> = (0..dice.len()).collect();
+            order.sort_by_key(|index| dice[*index].value);
+            let length = order.len();
+            let dropped = match selection {
+                Selection::KeepHighest(number) => &order[..length - number.min(length)],
+                Selection::KeepLowest(number) => &order[number.min(length)..],
+                Selection::DropHighest(number) => &order[length - number.min(length)..],
+                Selection::DropLowest(number) => &order[..number.min(length)],
+            };
+            for index in dropped {
+                dice[*index].kept = false;
+            }
+        }
+        dice
+    }
+}
+
+/// A single die rolled as part of a dice expression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Die {
+    value: u32,
+    exploded: bool,
+    kept: bool,
+}
+
+impl Die {
+    fn new(value: u32, exploded: bool) -> Die {
+        Die {
+            value,
+            exploded,
+            kept: true,
+        }
+    }
+}
+
+impl fmt::Display for Die {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marker = if self.exploded { "!" } else { "" };
+        if self.kept {
+            write!(f, "{}{}", self.value, marker)
+        } else {
+            write!(f, "~~{}{}~~", self.value, marker)
+        }
+    }
+}
+
+/// The result of a single term of a dice expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum TermResult {
+    Constant(Sign, u32),
+    Dice(Sign, Vec<Die>),
+}
+
+impl TermResult {
+    fn total(&self) -> i64 {
+        let (sign, total) = match self {
+            TermResult::Constant(sign, value) => (sign, i64::from(*value)),
+            TermResult::Dice(sign, dice) => (
+                sign,
+                dice.iter()
+                    .filter(|die| die.kept)
+                    .map(|die| i64::from(die.value))
+                    .sum(),
+            ),
+        };
+        match sign {
+            Sign::Plus => total,
+            Sign::Minus => -total,
+        }
+    }
+}
+
+/// A single evaluation of every term of a dice expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Evaluation {
+    terms: Vec<TermResult>,
+    total: i64,
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, term) in self.terms.iter().enumerate() {
+            let sign = match term {
+                TermResult::Constant(sign, _) | TermResult::Dice(sign, _) => sign,
+            };
+            match (index, sign) {
+                (0, Sign::Plus) => Ok(()),
+                (0, Sign::Minus) => write!(f, "-"),
+                (_, Sign::Plus) => write!(f, " + "),
+                (_, Sign::Minus) => write!(f, " - "),
+            }?;
+            match term {
+                TermResult::Constant(_, value) => write!(f, "{}", value)?,
+                TermResult::Dice(_, dice) => {
+                    write!(f, "(")?;
+                    for (index, die) in dice.iter().take(MAXIMUM_ROLLS_DISPLAY).enumerate() {
+                        write!(f, "{}{}", if index > 0 { ", " } else { "" }, die)?;
+                    }
+                    if dice.len() > MAXIMUM_ROLLS_DISPLAY {
+                        write!(f, ", …")?;
+                    }
+                    write!(f, ")")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The detailed result of rolling a dice expression.
+///
+/// It includes the dice rolled for each term, so that the working can be shown to the user, and
+/// for a roll with advantage or disadvantage, the evaluation that wasn't kept.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiceExpressionResult {
+    kept: Evaluation,
+    discarded: Option<(RollMode, Evaluation)>,
+}
+
+impl fmt::Display for DiceExpressionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "**{}** = {}", self.kept.total, self.kept)?;
+        match &self.discarded {
+            Some((mode, discarded)) => write!(
+                f,
+                ", with {} over ~~{}~~ = {}",
+                mode, discarded.total, discarded
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg32;
+
+    fn dice(count: usize, sides: u32) -> Dice {
+        Dice {
+            count,
+            sides,
+            exploding: false,
+            selection: None,
+        }
+    }
+
+    #[test]
+    fn parse_errors_report_their_position() {
+        assert_eq!(
+            DiceExpression::parse("2d"),
+            Err(ParserError::Expected(2, "the number of sides"))
+        );
+        assert_eq!(
+            DiceExpression::parse("3d6 +"),
+            Err(ParserError::Expected(5, "a number or dice"))
+        );
+        assert_eq!(
+            DiceExpression::parse("4d6kh5"),
+            Err(ParserError::InvalidSelection(3))
+        );
+        assert_eq!(
+            DiceExpression::parse("1d1!"),
+            Err(ParserError::InvalidExplosion(3))
+        );
+        assert_eq!(
+            DiceExpression::parse("4d6kh1kh1"),
+            Err(ParserError::DuplicateModifier(6))
+        );
+        assert_eq!(
+            DiceExpression::parse("1d6 + 99999999999"),
+            Err(ParserError::NumberTooLarge(6))
+        );
+        assert_eq!(
+            DiceExpression::parse("1d6 + 99999999999")
+                .unwrap_err()
+                .to_string(),
+            "That number is too large at character 7."
+        );
+    }
+
+    #[test]
+    fn parse_with_advantage_or_disadvantage() {
+        let expression = DiceExpression::parse("1d20 + 5 with advantage").unwrap();
+        assert_eq!(
+            expression,
+            DiceExpression {
+                terms: vec![
+                    (Sign::Plus, Term::Dice(dice(1, 20))),
+                    (Sign::Plus, Term::Constant(5)),
+                ],
+                mode: RollMode::Advantage,
+            }
+        );
+        assert_eq!(expression.to_string(), "1d20 + 5 with advantage");
+        assert_eq!(
+            DiceExpression::parse("2d6 dis").map(|expression| expression.mode),
+            Ok(RollMode::Disadvantage)
+        );
+        assert_eq!(
+            DiceExpression::parse("2d6").map(|expression| expression.mode),
+            Ok(RollMode::Normal)
+        );
+    }
+
+    #[test]
+    fn selection_keeps_and_drops_the_right_dice() {
+        let selections = [
+            (Selection::KeepHighest(3), 1..4),
+            (Selection::KeepLowest(1), 0..1),
+            (Selection::DropHighest(2), 0..2),
+            (Selection::DropLowest(1), 1..4),
+        ];
+        for seed in 0..100 {
+            for (selection, kept) in selections.iter() {
+                let mut rng = Pcg32::seed_from_u64(seed);
+                let rolled = Dice {
+                    selection: Some(*selection),
+                    ..dice(4, 6)
+                }
+                .roll(&mut rng);
+                let mut values: Vec<u32> = rolled.iter().map(|die| die.value).collect();
+                values.sort();
+                let mut kept_values: Vec<u32> = rolled
+                    .iter()
+                    .filter(|die| die.kept)
+                    .map(|die| die.value)
+                    .collect();
+                kept_values.sort();
+                assert_eq!(kept_values, &values[kept.clone()], "{:?}", selection);
+            }
+        }
+    }
+
+    #[test]
+    fn advantage_keeps_the_better_total() {
+        let advantage = DiceExpression::parse("1d20 with advantage").unwrap();
+        let disadvantage = DiceExpression::parse("1d20 with disadvantage").unwrap();
+        let mut rng = Pcg32::seed_from_u64(0);
+        for _ in 0..100 {
+            let result = advantage.roll(&mut rng);
+            let (_, discarded) = result.discarded.unwrap();
+            assert!(result.kept.total >= discarded.total);
+            let result = disadvantage.roll(&mut rng);
+            let (_, discarded) = result.discarded.unwrap();
+            assert!(result.kept.total <= discarded.total);
+        }
+    }
+
+    #[test]
+    fn explosions_are_capped() {
+        // Always rolls the highest value, so every die would explode forever.
+        let mut rng = StepRng::new(!0, 0);
+        let rolled = Dice {
+            exploding: true,
+            ..dice(2, 6)
+        }
+        .roll(&mut rng);
+        assert_eq!(rolled.len(), 2 + MAXIMUM_EXPLOSIONS);
+        assert_eq!(
+            rolled.iter().filter(|die| die.exploded).count(),
+            MAXIMUM_EXPLOSIONS
+        );
+        assert!(rolled.iter().all(|die| die.value == 6));
+    }
+}
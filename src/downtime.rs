@@ -0,0 +1,74 @@
+use crate::character::{ActionName, XpTrack};
+use regex::Regex;
+use std::fmt;
+
+/// The number of downtime activities that a character may take in each downtime phase.
+pub const MAXIMUM_DOWNTIME_ACTIVITIES: usize = 2;
+
+/// The experience marked by training during downtime.
+pub const TRAINING_XP: usize = 1;
+
+/// An activity that a character takes during a downtime phase.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DowntimeActivity {
+    /// Tick the character's healing clock, rolling their Doctor rating.
+    Recover,
+    /// Indulge the character's vice, rolling their lowest attribute to clear stress.
+    Vice,
+    /// Mark experience on one of the character's XP tracks.
+    Train(XpTrack),
+    /// Tick a clock for a long-term project, rolling an action rating.
+    LongTermProject {
+        project: String,
+        segments: usize,
+        action: Option<ActionName>,
+    },
+}
+
+impl DowntimeActivity {
+    /// Parse a downtime activity, e.g. `train insight` or `long-term-project "Fix the drive" 8`.
+    pub fn parse(string: &str) -> Option<DowntimeActivity> {
+        lazy_static! {
+            static ref TRAIN_REGEX: Regex = Regex::new(r"(?i)^train +(\w+)$").unwrap();
+            static ref PROJECT_REGEX: Regex = Regex::new(
+                r#"(?i)^(?:long-term-project|project) +(?:"([^"]+)"|(\S+)) +(\d+)(?: +(?:with +)?(\w+))?$"#
+            )
+            .unwrap();
+        }
+
+        let string = string.trim();
+        if string.eq_ignore_ascii_case("recover") {
+            Some(DowntimeActivity::Recover)
+        } else if string.eq_ignore_ascii_case("vice") {
+            Some(DowntimeActivity::Vice)
+        } else if let Some(captures) = TRAIN_REGEX.captures(string) {
+            XpTrack::parse(captures.get(1)?.as_str()).map(DowntimeActivity::Train)
+        } else {
+            let captures = PROJECT_REGEX.captures(string)?;
+            let project = captures.get(1).or_else(|| captures.get(2))?.as_str();
+            let segments = captures.get(3)?.as_str().parse::<usize>().ok()?;
+            let action = match captures.get(4) {
+                Some(action) => Some(ActionName::parse(action.as_str())?),
+                None => None,
+            };
+            Some(DowntimeActivity::LongTermProject {
+                project: project.trim().to_owned(),
+                segments,
+                action,
+            })
+        }
+    }
+}
+
+impl fmt::Display for DowntimeActivity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DowntimeActivity::Recover => write!(f, "recover"),
+            DowntimeActivity::Vice => write!(f, "indulge vice"),
+            DowntimeActivity::Train(track) => write!(f, "train {}", track.as_str()),
+            DowntimeActivity::LongTermProject { project, .. } => {
+                write!(f, "work on the {} project", project)
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::roll;
+use crate::roll::{Roll, RollKind};
+use regex::Regex;
+use std::fmt;
+
+/// An engagement roll, made at the start of a job to determine the crew's starting position.
+///
+/// The roll starts with one die, adding a die for a bold or daring plan, and a die for each
+/// advantage, while removing a die for each complication. When the target is a faction, a die is
+/// added for each tier the crew has above the faction, or removed for each tier below.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Engagement {
+    pub bold: bool,
+    pub advantages: usize,
+    pub complications: usize,
+    /// The name of the faction targeted by the plan, if any.
+    pub target: Option<String>,
+    /// The crew's tier minus the target faction's tier.
+    pub tier_difference: i32,
+}
+
+impl Engagement {
+    /// Parse the plan and details of an engagement roll, e.g. `bold +2 -1 vs Hegemony`.
+    pub fn parse(string: &str) -> Option<Engagement> {
+        lazy_static! {
+            static ref OPTION_REGEX: Regex =
+                Regex::new(r#"(?i)^ *(?:(bold|daring)|\+(\d+)|-(\d+)|vs +(?:"([^"]+)"|(\S+)))"#)
+                    .unwrap();
+        }
+
+        let mut engagement = Engagement::default();
+        let mut rest = string.trim_end();
+        while !rest.is_empty() {
+            let captures = OPTION_REGEX.captures(rest)?;
+            if captures.get(1).is_some() {
+                engagement.bold = true;
+            } else if let Some(advantages) = captures.get(2) {
+                engagement.advantages = advantages.as_str().parse::<usize>().ok()?;
+            } else if let Some(complications) = captures.get(3) {
+                engagement.complications = complications.as_str().parse::<usize>().ok()?;
+            } else if let Some(target) = captures.get(4).or_else(|| captures.get(5)) {
+                engagement.target = Some(target.as_str().to_owned());
+            }
+            rest = &rest[captures.get(0)?.end()..];
+        }
+        Some(engagement)
+    }
+
+    /// The number of dice rolled, which may not be less than zero.
+    pub fn dice(&self) -> usize {
+        (1 + self.bold as i32 + self.advantages as i32 - self.complications as i32
+            + self.tier_difference)
+            .max(0) as usize
+    }
+
+    pub fn to_roll(&self) -> Result<Roll, roll::Error> {
+        Roll::new(self.dice()).map(|roll| roll.with_kind(RollKind::Engagement))
+    }
+}
+
+impl fmt::Display for Engagement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "1d base")?;
+        if self.bold {
+            write!(f, ", +1d bold plan")?;
+        }
+        if self.advantages > 0 {
+            write!(f, ", +{}d advantages", self.advantages)?;
+        }
+        if self.complications > 0 {
+            write!(f, ", -{}d complications", self.complications)?;
+        }
+        if let Some(target) = self.target.as_ref() {
+            if self.tier_difference > 0 {
+                write!(
+                    f,
+                    ", +{}d higher tier than {}",
+                    self.tier_difference, target
+                )?;
+            } else if self.tier_difference < 0 {
+                write!(f, ", {}d lower tier than {}", self.tier_difference, target)?;
+            }
+        }
+        Ok(())
+    }
+}
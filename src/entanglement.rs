@@ -0,0 +1,80 @@
+use crate::roll;
+use crate::roll::{Roll, RollKind};
+use regex::Regex;
+use std::fmt;
+
+/// An entanglement roll, made after a job to find out what trouble catches up with the crew.
+///
+/// The roll uses one die for each of the crew's wanted levels. When the entanglement comes from a
+/// faction, a die is added for each tier the faction has above the crew, or removed for each tier
+/// below. The crew's heat picks the column of the entanglements table, and the roll picks the row.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Entanglement {
+    pub heat: usize,
+    pub wanted_level: usize,
+    /// The name of the faction the entanglement comes from, if any.
+    pub faction: Option<String>,
+    /// The crew's tier minus the faction's tier.
+    pub tier_difference: i32,
+}
+
+impl Entanglement {
+    /// Parse the faction of an entanglement roll, e.g. `vs Hegemony`, which may be omitted.
+    pub fn parse(string: &str) -> Option<Entanglement> {
+        lazy_static! {
+            static ref FACTION_REGEX: Regex =
+                Regex::new(r#"(?i)^(?:vs|from) +(?:"([^"]+)"|(\S+))$"#).unwrap();
+        }
+
+        let string = string.trim();
+        if string.is_empty() {
+            return Some(Entanglement::default());
+        }
+        let captures = FACTION_REGEX.captures(string)?;
+        let faction = captures.get(1).or_else(|| captures.get(2))?;
+        Some(Entanglement {
+            faction: Some(faction.as_str().to_owned()),
+            ..Entanglement::default()
+        })
+    }
+
+    /// The number of dice rolled, which may not be less than zero.
+    pub fn dice(&self) -> usize {
+        (self.wanted_level as i32 - self.tier_difference).max(0) as usize
+    }
+
+    pub fn to_roll(&self) -> Result<Roll, roll::Error> {
+        Roll::new(self.dice()).map(|roll| roll.with_kind(RollKind::Entanglement))
+    }
+
+    /// The column of the entanglements table for the crew's heat.
+    pub fn column(&self) -> &str {
+        match self.heat {
+            0..=3 => "0-3 heat",
+            4..=5 => "4-5 heat",
+            _ => "6+ heat",
+        }
+    }
+}
+
+impl fmt::Display for Entanglement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d wanted level", self.wanted_level)?;
+        if let Some(faction) = self.faction.as_ref() {
+            if self.tier_difference < 0 {
+                write!(
+                    f,
+                    ", +{}d {} has a higher tier",
+                    -self.tier_difference, faction
+                )?;
+            } else if self.tier_difference > 0 {
+                write!(
+                    f,
+                    ", -{}d {} has a lower tier",
+                    self.tier_difference, faction
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,15 +1,39 @@
+use crate::assist::Assist;
+use crate::bargain::Bargain;
+use crate::campaign::Campaign;
 use crate::channel::Channel;
-use crate::character::Character;
-use crate::character_roll::CharacterRoll;
+use crate::character::{
+    ActionName, Character, XpTrack, HEALING_CLOCK_SEGMENTS, MAXIMUM_ACTION_RATING, MAXIMUM_STRESS,
+};
+use crate::character_roll;
+use crate::character_roll::{ActionOptions, CharacterRoll, Check, Modifier};
+use crate::clock::Clock;
 use crate::command;
 use crate::command::{Command, CommandResult};
+use crate::dice_expression::DiceExpression;
+use crate::downtime::{DowntimeActivity, MAXIMUM_DOWNTIME_ACTIVITIES, TRAINING_XP};
+use crate::engagement::Engagement;
+use crate::entanglement::Entanglement;
 use crate::error::Error;
+use crate::faction::{Faction, Hold, MAXIMUM_STATUS};
+use crate::group_action::{GroupAction, GROUP_ACTION_WINDOW};
+use crate::harm::{Harm, MAXIMUM_HARM_LEVEL};
+use crate::heat::{HeatChange, JobEnd, MAXIMUM_HEAT, MAXIMUM_WANTED_LEVEL};
 use crate::intent_logger::log_intent_result;
+use crate::item::{Item, Loadout};
+use crate::playbook::{Playbook, PlaybookList};
+use crate::project::Project;
 use crate::response::Response;
-use crate::roll::Roll;
+use crate::roll::{Roll, RollKind, RollResult};
+use crate::roll_history::{RollFilter, RollRecord};
+use crate::roll_stats::RollStats;
+use crate::ship::{ResourceName, Ship, SystemName};
+use crate::trauma::{Trauma, MAXIMUM_TRAUMAS};
 use log::{error, info};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::Connection;
+use rusqlite::Result as RusqliteResult;
 use snips_nlu_lib::SnipsNluEngine;
 use snips_nlu_ontology::IntentParserResult;
 use std::convert::identity;
@@ -17,20 +41,59 @@ use std::sync::RwLock;
 use symspell::{SymSpell, UnicodeStringStrategy};
 
 use serenity::{
+    cache::CacheRwLock,
     model::{
         channel::Message,
         gateway::Ready,
-        id::{ChannelId, UserId},
+        id::{ChannelId, GuildId, UserId},
     },
     prelude::*,
 };
 
 const CHARACTER_NOT_FOUND_WARNING_TEXT: &str =
-    "Couldn't find any entry for character.";
+    "Couldn't find any entry for character. Try typing `!char create` to create one.";
+
+const NO_PENDING_TRAUMA_WARNING_TEXT: &str =
+    "You have no trauma to choose. Trauma is suffered when your stress exceeds 9.";
+
+const NO_GROUP_ACTION_WARNING_TEXT: &str =
+    "There is no group action underway in this channel. Type `!group <action>` to lead one.";
+
+/// The stress taken by a character when they assist another character.
+const ASSIST_STRESS: usize = 1;
+
+/// The experience marked by a character when they make a desperate action roll.
+const DESPERATE_ROLL_XP: usize = 1;
+
+const FATAL_HARM_WARNING_TEXT: &str =
+    "Your character is already suffering fatal harm, and can't take any more.";
+
+const CHARACTER_ALREADY_EXISTS_WARNING_TEXT: &str =
+    "You already have a character with that name in this channel. Try typing `!char list` to see your characters.";
+
+const CLOCK_NOT_FOUND_WARNING_TEXT: &str =
+    "Couldn't find a clock with that name in this channel. Try typing `!clock list` to see them.";
 
-// TODO Remove this, attributes are not optional
-const ATTRIBUTE_NOT_SET_WARNING_TEXT: &str =
-    "Couldn't find required attribute ratings for character.";
+const CLOCK_ALREADY_EXISTS_WARNING_TEXT: &str =
+    "There is already a clock with that name in this channel. Try typing `!clock list` to see it.";
+
+const SHIP_NOT_FOUND_WARNING_TEXT: &str =
+    "Couldn't find a ship for this channel. Try typing `!ship create` to create one.";
+
+const SHIP_ALREADY_EXISTS_WARNING_TEXT: &str =
+    "This channel already has a ship. Try typing `!ship show` to see it.";
+
+const ITEM_NOT_FOUND_WARNING_TEXT: &str =
+    "Couldn't find an item with that name on your item list. Try typing `!items` to see them, or `!item add \"Spacesuit\" 1` to add one.";
+
+const ITEM_ALREADY_EXISTS_WARNING_TEXT: &str =
+    "There is already an item with that name on your item list. Try typing `!items` to see it.";
+
+const FACTION_NOT_FOUND_WARNING_TEXT: &str =
+    "Couldn't find a faction with that name in this channel. Try typing `!faction list` to see them.";
+
+const FACTION_ALREADY_EXISTS_WARNING_TEXT: &str =
+    "There is already a faction with that name in this channel. Try typing `!faction list` to see it.";
 
 enum Action {
     IgnoreChannelDisabled,
@@ -73,6 +136,7 @@ impl Handler {
         message: &Message,
         is_admin: bool,
         is_private: bool,
+        cache: &CacheRwLock,
     ) -> Action {
         command_result.map_or(Action::IgnoreCommandMissing, |command_result| {
             command_result
@@ -88,14 +152,12 @@ impl Handler {
                         Ok(command) => {
                             if !is_admin && !channel.enabled {
                                 Action::IgnoreChannelDisabled
+                            } else if !is_admin && command.is_admin() {
+                                Action::Respond(Response::Warning(format!("It looks like you're trying to {}. Only a server administrator can do that.", command.description())))
                             } else if is_private && !command.is_private() {
                                 Action::Respond(Response::Warning(format!("It looks like you're trying to {}. You can't do that in a private message.", command.description())))
                             } else {
-                                Action::Respond(self.run_command(
-                                    command,
-                                    message.channel_id,
-                                    message.author.id,
-                                ))
+                                Action::Respond(self.run_command(command, message, cache))
                             }
                         }
                         Err(error) => Action::Respond(error.into_response()),
@@ -105,11 +167,98 @@ impl Handler {
         })
     }
 
-    fn run_command(&self, command: Command, channel_id: ChannelId, author_id: UserId) -> Response {
+    fn run_command(&self, command: Command, message: &Message, cache: &CacheRwLock) -> Response {
+        let channel_id = message.channel_id;
+        let author_id = message.author.id;
         match command {
-            Command::CharacterRoll(roll) => self.character_roll(&roll, channel_id, author_id),
+            Command::AddFaction(name, tier, hold) => self.add_faction(name, tier, hold, channel_id),
+            Command::AddItem(name, load) => self.add_item(&name, load, channel_id, author_id),
+            Command::AddShipUpgrade(upgrade) => self.add_ship_upgrade(&upgrade, channel_id),
+            Command::Advance(action) => self.advance(action, channel_id, author_id),
+            Command::AdjustFactionStatus(name, amount) => {
+                self.adjust_faction_status(&name, amount, channel_id)
+            }
+            Command::Assist(user_id) => self.assist(user_id, channel_id, author_id),
+            Command::CharacterOdds(roll, assisted) => self.character_odds(&roll, assisted, message),
+            Command::CharacterRoll(roll) => self.character_roll(&roll, message),
+            Command::ChooseTrauma(trauma) => self.choose_trauma(trauma, channel_id, author_id),
+            Command::ClearHarm => self.clear_harm(channel_id, author_id),
+            Command::CreateCharacter(name, playbook) => {
+                let name = name.unwrap_or_else(|| message.author.name.clone());
+                self.create_character(&name, playbook, channel_id, author_id)
+            }
+            Command::CreateClock(clock) => self.create_clock(&clock, channel_id),
+            Command::CreateShip => self.create_ship(channel_id),
+            Command::DeleteClock(name) => self.delete_clock(&name, channel_id),
+            Command::DeleteFaction(name) => self.delete_faction(&name, channel_id),
+            Command::Downtime(activity) => self.downtime(&activity, message),
+            Command::EndJob(job_end) => self.end_job(&job_end, channel_id),
+            Command::EndSession => self.end_session(channel_id),
+            Command::Engagement(engagement) => self.engagement(engagement, message),
+            Command::Entanglement(entanglement) => self.entanglement(entanglement, message),
+            Command::HealHarm => self.heal_harm(channel_id, author_id),
             Command::Help => Handler::help(),
-            Command::Roll(roll) => Handler::roll(roll),
+            Command::JoinGuildCampaign => match message.guild_id {
+                Some(guild_id) => self.link_campaign(Campaign::Guild(guild_id), message),
+                None => Response::Warning(
+                    "Only channels in a server can join the server's campaign.".to_owned(),
+                ),
+            },
+            Command::LinkCampaign(campaign_channel_id) => {
+                if Handler::is_guild_channel(campaign_channel_id, message.guild_id, cache) {
+                    self.link_campaign(Campaign::Channel(campaign_channel_id), message)
+                } else {
+                    Response::Warning(format!(
+                        "Couldn't link this channel to <#{}>, as it isn't a channel in this server.",
+                        campaign_channel_id
+                    ))
+                }
+            }
+            Command::ListAbilities(playbook) => {
+                self.list_abilities(playbook, channel_id, author_id)
+            }
+            Command::ListBargains => self.list_bargains(channel_id),
+            Command::ListCharacters => self.list_characters(channel_id, author_id),
+            Command::ListClocks => self.list_clocks(channel_id),
+            Command::ListFactions => self.list_factions(channel_id),
+            Command::MarkXp(track, amount) => self.mark_xp(track, amount, channel_id, author_id),
+            Command::Odds(roll) => Handler::odds(roll),
+            Command::RemoveItem(name) => self.remove_item(&name, channel_id, author_id),
+            Command::RemoveShipUpgrade(upgrade) => self.remove_ship_upgrade(&upgrade, channel_id),
+            Command::ResolveBargains => self.resolve_bargains(channel_id),
+            Command::ResolveGroupAction => self.resolve_group_action(message),
+            Command::Roll(roll) => self.roll(roll, message),
+            Command::RollExpression(expression) => Handler::roll_expression(&expression),
+            Command::SetActionRating(action, rating) => {
+                self.set_action_rating(action, rating, channel_id, author_id)
+            }
+            Command::SetCrewTier(tier) => self.set_crew_tier(tier, channel_id),
+            Command::SetFactionHold(name, hold) => self.set_faction_hold(&name, hold, channel_id),
+            Command::SetFactionTier(name, tier) => self.set_faction_tier(&name, tier, channel_id),
+            Command::SetLoadout(loadout) => self.set_loadout(loadout, channel_id, author_id),
+            Command::SetShipResource(resource, amount) => {
+                self.set_ship_resource(resource, amount, channel_id)
+            }
+            Command::SetShipSystem(system, rating) => {
+                self.set_ship_system(system, rating, channel_id)
+            }
+            Command::ShowCampaign => self.show_campaign(message),
+            Command::ShowCharacter => self.show_character(channel_id, author_id),
+            Command::ShowHeat => self.show_heat(channel_id),
+            Command::ShowHistory(filter) => self.show_history(&filter, message),
+            Command::ShowLoad => self.show_load(channel_id, author_id),
+            Command::ShowShip => self.show_ship(channel_id),
+            Command::ShowStats(user_id, session) => self.show_stats(user_id, session, message),
+            Command::StartDowntime => self.start_downtime(channel_id),
+            Command::StartGroupAction(action) => self.start_group_action(action, message),
+            Command::StartSession => self.start_session(message),
+            Command::TakeHarm(level, description) => {
+                self.take_harm(level, description, channel_id, author_id)
+            }
+            Command::TickClock(name, ticks) => self.tick_clock(&name, ticks, channel_id),
+            Command::UnlinkCampaign => self.unlink_campaign(channel_id),
+            Command::UseCharacter(name) => self.use_character(&name, channel_id, author_id),
+            Command::UseItem(name) => self.use_item(&name, channel_id, author_id),
         }
     }
 
@@ -131,9 +280,298 @@ impl Handler {
             .unwrap_or(())
     }
 
-    fn character_roll(
+    fn character_roll(&self, character_roll: &CharacterRoll, message: &Message) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| match &character_roll.check {
+                Check::System(system) => {
+                    Handler::perform_system_roll(&connection, *system, message)
+                }
+                Check::Attribute(_) | Check::Action(_, _) => {
+                    self.perform_character_roll(&mut connection, character_roll, message)
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    /// Roll one of the ship's systems, which the crew does together, so it doesn't need a
+    /// character.
+    fn perform_system_roll(
+        connection: &Connection,
+        system: SystemName,
+        message: &Message,
+    ) -> Result<Response, Response> {
+        let ship = Handler::get_ship(connection, message.channel_id)?;
+        let check = Check::System(system);
+        let roll =
+            Roll::new(ship.system(system)).map_err(|error| Response::Warning(error.to_string()))?;
+        let mut rng = rand::thread_rng();
+        let result = roll.roll(&mut rng);
+        Handler::record_roll(
+            connection,
+            message,
+            Some(&check.to_string()),
+            None,
+            &roll,
+            &result,
+        );
+        Ok(Response::DiceRoll(format!(
+            "rolled {} ({}) = {}",
+            check, roll, result
+        )))
+    }
+
+    fn system_odds(&self, system: SystemName, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| Handler::get_ship(&connection, channel_id))
+            .and_then(|ship| {
+                Roll::new(ship.system(system)).map_err(|error| Response::Warning(error.to_string()))
+            })
+            .map(|roll| {
+                Response::DiceRoll(format!(
+                    "the odds of rolling {} ({}) are:\n{}",
+                    Check::System(system),
+                    roll,
+                    roll.odds()
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn character_odds(
+        &self,
+        character_roll: &CharacterRoll,
+        assisted: bool,
+        message: &Message,
+    ) -> Response {
+        if let Check::System(system) = &character_roll.check {
+            return self.system_odds(*system, message.channel_id);
+        }
+        let channel_id = message.channel_id;
+        let author_id = message.author.id;
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let character = match character_roll.character.as_ref() {
+                    Some(name) => {
+                        Handler::get_named_character(&connection, channel_id, author_id, name)?
+                    }
+                    None => Handler::get_character(&connection, channel_id, author_id)?,
+                };
+                let ship = Ship::get(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                let assist = Assist::get(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                Ok((character, ship, assist))
+            })
+            .and_then(|(character, ship, assist)| {
+                let assisted_by = assist.map(|assist| assist.assistant_id);
+                let character_roll = match &character_roll.check {
+                    Check::Action(action, options) => CharacterRoll {
+                        check: Check::Action(
+                            *action,
+                            ActionOptions {
+                                assisted_by,
+                                ..options.clone()
+                            },
+                        ),
+                        ..character_roll.clone()
+                    },
+                    Check::Attribute(_) | Check::System(_) => character_roll.clone(),
+                };
+                // Only action rolls can be assisted.
+                let extra_assist = assisted
+                    && assisted_by.is_none()
+                    && matches!(character_roll.check, Check::Action(_, _));
+                let roll = character_roll
+                    .to_unchecked_roll(&character, ship.as_ref())
+                    .and_then(|roll| {
+                        roll.with_extra_dice(if extra_assist { 1 } else { 0 })
+                            .map_err(character_roll::Error::RollInvalid)
+                    })
+                    .map_err(|error| Response::Warning(error.to_string()))?;
+                let mut modifiers = character_roll
+                    .modifiers(&character)
+                    .iter()
+                    .map(Modifier::to_string)
+                    .collect::<Vec<_>>();
+                if extra_assist {
+                    modifiers.push("+1d from an assist".to_owned());
+                }
+                Ok(Response::DiceRoll(format!(
+                    "the odds of rolling {}{} ({}{}) are:\n{}",
+                    character_roll.check,
+                    character_roll
+                        .character
+                        .as_ref()
+                        .map_or("".to_owned(), |_| format!(" as {}", character.name())),
+                    roll,
+                    if modifiers.is_empty() {
+                        "".to_owned()
+                    } else {
+                        format!(": {}", modifiers.join(", "))
+                    },
+                    roll.odds()
+                )))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    /// Make a roll for a character, recording it along with its cost and consequences, such as
+    /// stress, XP and gambits, all together in one transaction.
+    fn perform_character_roll(
         &self,
+        connection: &mut Connection,
         character_roll: &CharacterRoll,
+        message: &Message,
+    ) -> Result<Response, Response> {
+        let channel_id = message.channel_id;
+        let author_id = message.author.id;
+        let character = match character_roll.character.as_ref() {
+            Some(name) => Handler::get_named_character(connection, channel_id, author_id, name)?,
+            None => Handler::get_character(connection, channel_id, author_id)?,
+        };
+
+        let assist = Assist::get(connection, channel_id, author_id)
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+        let character_roll = match &character_roll.check {
+            Check::Action(action, options) => CharacterRoll {
+                check: Check::Action(
+                    *action,
+                    ActionOptions {
+                        assisted_by: assist.map(|assist| assist.assistant_id),
+                        ..options.clone()
+                    },
+                ),
+                ..character_roll.clone()
+            },
+            Check::Attribute(_) | Check::System(_) => character_roll.clone(),
+        };
+        let ship = Ship::get(connection, channel_id)
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+
+        let roll = character_roll
+            .to_roll(&character, ship.as_ref())
+            .map_err(|error| Response::Warning(error.to_string()))?;
+        let mut rng = rand::thread_rng();
+        let result = roll.roll(&mut rng);
+        let transaction = connection
+            .transaction()
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+        Handler::record_roll(
+            &transaction,
+            message,
+            Some(&character_roll.check.to_string()),
+            Some(character.name()),
+            &roll,
+            &result,
+        );
+
+        let stress = character_roll
+            .stress(&result)
+            .map(|amount| character.apply_stress(amount));
+        if let Some(stress) = stress.as_ref() {
+            Character::update_stress(&transaction, character.id(), stress)
+                .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+        }
+
+        let xp = character_roll
+            .xp()
+            .map(|track| character.apply_xp(track, DESPERATE_ROLL_XP));
+        if let Some(xp) = xp.as_ref() {
+            Character::add_xp(&transaction, character.id(), xp.track, xp.amount)
+                .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+        }
+
+        let gambits = if ship.is_some() {
+            character_roll.gambits(&result)
+        } else {
+            0
+        };
+        if gambits != 0 {
+            Ship::add_gambits(&transaction, channel_id, gambits)
+                .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+        }
+
+        let mut joined_group_action = false;
+        if let Check::Action(action, options) = &character_roll.check {
+            if let Some(bargain) = options.bargain.as_ref() {
+                Bargain::add(&transaction, message, bargain)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+            }
+            if options.assisted_by.is_some() {
+                Assist::remove(&transaction, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+            }
+            joined_group_action = GroupAction::join(&transaction, message, *action, &result)
+                .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                > 0;
+        }
+
+        transaction
+            .commit()
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+
+        let modifiers = character_roll.modifiers(&character);
+        let bargain = match &character_roll.check {
+            Check::Action(_, options) => options.bargain.as_ref(),
+            Check::Attribute(_) | Check::System(_) => None,
+        };
+        Ok(Response::DiceRoll(format!(
+            "rolled {}{} ({}{}) = {}{}{}{}{}{}",
+            character_roll.check,
+            character_roll
+                .character
+                .as_ref()
+                .map_or("".to_owned(), |_| format!(" as {}", character.name())),
+            roll,
+            modifiers
+                .iter()
+                .enumerate()
+                .fold(String::new(), |text, (index, modifier)| format!(
+                    "{}{}{}",
+                    text,
+                    if index > 0 { ", " } else { ": " },
+                    modifier
+                )),
+            result,
+            stress.map_or("".to_owned(), |stress| format!(", and {}", stress)),
+            xp.map_or("".to_owned(), |xp| format!(
+                "\nRolled from a desperate position, {} {}.",
+                if xp.amount > 0 { "and" } else { "but" },
+                xp
+            )),
+            match (gambits, ship.as_ref()) {
+                (gambits, Some(ship)) if gambits < 0 => format!(
+                    "\nSpent a gambit ({} left).",
+                    ship.resource(ResourceName::Gambits).saturating_sub(1)
+                ),
+                (gambits, Some(ship)) if gambits > 0 => format!(
+                    "\nRolled a 6 on a risky roll, and the crew earned a gambit (now {}).",
+                    ship.resource(ResourceName::Gambits) + 1
+                ),
+                _ => "".to_owned(),
+            },
+            bargain.map_or("".to_owned(), |bargain| format!(
+                "\nAccepted a devil's bargain: \"{}\"",
+                bargain
+            )),
+            if joined_group_action {
+                "\nJoined the group action."
+            } else {
+                ""
+            }
+        )))
+    }
+
+    fn mark_xp(
+        &self,
+        track: XpTrack,
+        amount: usize,
         channel_id: ChannelId,
         author_id: UserId,
     ) -> Response {
@@ -141,107 +579,1885 @@ impl Handler {
             .get()
             .map_err(|error| Response::Error(Error::R2D2Error(error)))
             .and_then(|connection| {
-                Character::get(&connection, channel_id, author_id)
-                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                let character = Character::get(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                    .ok_or_else(|| {
+                        Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned())
+                    })?;
+                let xp = character.apply_xp(track, amount);
+                if xp.amount == 0 {
+                    return Err(Response::Warning(format!("You can't mark XP, as {}.", xp)));
+                }
+                Character::add_xp(&connection, character.id(), xp.track, xp.amount)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                Ok(Response::CharacterSheet(format!("{}.", xp)))
             })
-            .and_then(|character| {
-                character
+            .unwrap_or_else(identity)
+    }
+
+    fn advance(&self, action: ActionName, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let character = Character::get(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                    .ok_or_else(|| {
+                        Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned())
+                    })?;
+                let attribute_track = XpTrack::for_attribute(action.attribute());
+                let track = [attribute_track, XpTrack::Playbook]
+                    .iter()
+                    .cloned()
+                    .find(|track| character.xp(*track) >= track.maximum())
+                    .ok_or_else(|| {
+                        Response::Warning(format!(
+                            "You can't advance {} yet. Fill your {} or Playbook XP track first.",
+                            action.as_str(),
+                            attribute_track.as_str()
+                        ))
+                    })?;
+                if character.action(action).map_or(0, |rating| rating.rating)
+                    >= MAXIMUM_ACTION_RATING
+                {
+                    return Err(Response::Warning(format!(
+                        "Your {} rating is already at the maximum of {}.",
+                        action.as_str(),
+                        MAXIMUM_ACTION_RATING
+                    )));
+                }
+                Character::advance(&connection, character.id(), action, track)
+                    .and_then(|_| Character::get(&connection, channel_id, author_id))
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                    .map(|character| {
+                        Response::CharacterSheet(format!(
+                            "advanced {} using their {} XP.\n{}",
+                            action.as_str(),
+                            track.as_str(),
+                            character
+                        ))
+                    })
                     .ok_or_else(|| Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned()))
             })
-            .and_then(|character| {
-                character_roll
-                    .to_roll(&character)
-                    .ok_or_else(|| Response::Warning(ATTRIBUTE_NOT_SET_WARNING_TEXT.to_owned()))
+            .unwrap_or_else(identity)
+    }
+
+    fn start_session(&self, message: &Message) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Channel::start_session(&connection, message)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
             })
-            .map(|roll| {
-                let mut rng = rand::thread_rng();
-                let result = roll.roll(&mut rng);
-                Response::DiceRoll(format!(
-                    "rolled {} ({}) = {}",
-                    character_roll.check, roll, result
+            .map(|_| {
+                Response::CharacterSheet(
+                    "started a session. Type `!stats session` for a recap of the rolls made from now on, and `!session end` at the end of play."
+                        .to_owned(),
+                )
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn end_session(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Character::get_playbooks(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|playbooks| {
+                let triggers = playbooks
+                    .iter()
+                    .filter_map(|playbook| Playbook::get(playbook))
+                    .map(|playbook| format!("\n  {}: {}", playbook.name, playbook.xp_trigger))
+                    .collect::<String>();
+                Response::CharacterSheet(format!(
+                    "ended the session. Each player marks XP on their playbook track (`!xp playbook`) for each of the following, or 2 XP if it happened a lot:\n\
+                     • You addressed a challenge with your playbook's XP trigger.{}\n\
+                     • You expressed your beliefs, drives, heritage or background.\n\
+                     • You struggled with issues from your vice or traumas during the session.\n\
+                     Type `!stats session` for a recap of the session's rolls.",
+                    triggers
                 ))
             })
             .unwrap_or_else(identity)
     }
 
-    fn help() -> Response {
-        Response::Help(
-            "Try typing the following:\n\
-             • \"Roll three dice\"\n\
-             • \"Do a hacking roll\"\n\
-             • \"Perform an insight resistance roll\""
-                .to_owned(),
-        )
+    fn downtime(&self, activity: &DowntimeActivity, message: &Message) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| Handler::perform_downtime(&mut connection, activity, message))
+            .unwrap_or_else(identity)
     }
 
-    fn roll(roll: Roll) -> Response {
+    /// Take a downtime activity for the author's character, recording its roll and results
+    /// together in one transaction.
+    fn perform_downtime(
+        connection: &mut Connection,
+        activity: &DowntimeActivity,
+        message: &Message,
+    ) -> Result<Response, Response> {
+        let channel_id = message.channel_id;
+        let author_id = message.author.id;
+        let character = Character::get(connection, channel_id, author_id)
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+            .ok_or_else(|| Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned()))?;
+        if character.downtime_activities() >= MAXIMUM_DOWNTIME_ACTIVITIES {
+            return Err(Response::Warning(format!(
+                "You've already taken {} downtime activities in this downtime phase. The GM can start a new downtime phase with `!downtime start`.",
+                MAXIMUM_DOWNTIME_ACTIVITIES
+            )));
+        }
+
+        let transaction = connection
+            .transaction()
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
         let mut rng = rand::thread_rng();
-        let result = roll.roll(&mut rng);
-        Response::DiceRoll(format!("rolled {} = {}", roll, result))
+        let description = match activity {
+            DowntimeActivity::Recover => {
+                let rating = character
+                    .action(ActionName::Doctor)
+                    .map_or(0, |rating| rating.rating);
+                let roll = Roll::new_unsafe(rating).with_kind(RollKind::Fortune);
+                let result = roll.roll(&mut rng);
+                Handler::record_roll(
+                    &transaction,
+                    message,
+                    Some("Doctor"),
+                    Some(character.name()),
+                    &roll,
+                    &result,
+                );
+                let healing = character.healing() + result.downtime_ticks();
+                let healed = healing >= HEALING_CLOCK_SEGMENTS;
+                if healed {
+                    Harm::heal(&transaction, character.id())
+                        .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                }
+                let healing = healing % HEALING_CLOCK_SEGMENTS;
+                Character::set_healing(&transaction, character.id(), healing)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                format!(
+                    "rolled Doctor to recover ({}) = {}\n{}",
+                    roll,
+                    result,
+                    if healed {
+                        format!(
+                            "Filled the healing clock, reducing each harm by one level. Healing clock now {}/{}.",
+                            healing, HEALING_CLOCK_SEGMENTS
+                        )
+                    } else {
+                        format!(
+                            "Ticked the healing clock by {} (now {}/{}).",
+                            result.downtime_ticks(),
+                            healing,
+                            HEALING_CLOCK_SEGMENTS
+                        )
+                    }
+                )
+            }
+            DowntimeActivity::Vice => {
+                let attribute = character.lowest_attribute();
+                let rating = character
+                    .attribute(attribute)
+                    .map_or(0, |rating| rating.rating);
+                let roll = Roll::new_unsafe(rating).with_kind(RollKind::Fortune);
+                let result = roll.roll(&mut rng);
+                Handler::record_roll(
+                    &transaction,
+                    message,
+                    Some(attribute.as_str()),
+                    Some(character.name()),
+                    &roll,
+                    &result,
+                );
+                let overindulged = result.result() as usize > character.stress();
+                let stress = character.apply_stress(-result.result());
+                Character::update_stress(&transaction, character.id(), &stress)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                format!(
+                    "rolled {} to indulge their vice ({}) = {}, and {}{}",
+                    attribute.as_str(),
+                    roll,
+                    result,
+                    stress,
+                    if overindulged {
+                        "\n**Overindulged!** You cleared more stress than you had. Choose a consequence: attract trouble, brag, lost, or tapped."
+                    } else {
+                        ""
+                    }
+                )
+            }
+            DowntimeActivity::Train(track) => {
+                let xp = character.apply_xp(*track, TRAINING_XP);
+                if xp.amount == 0 {
+                    return Err(Response::Warning(format!("You can't train, as {}.", xp)));
+                }
+                Character::add_xp(&transaction, character.id(), xp.track, xp.amount)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                format!("trained, and {}.", xp)
+            }
+            DowntimeActivity::LongTermProject {
+                project,
+                segments,
+                action,
+            } => {
+                let project = match Project::get(&transaction, channel_id, project)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                {
+                    Some(project) => project,
+                    None => {
+                        let project = Project {
+                            name: project.to_owned(),
+                            segments: *segments,
+                            ticks: 0,
+                        };
+                        Project::create(&transaction, channel_id, &project)
+                            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                        project
+                    }
+                };
+                if project.is_complete() {
+                    return Err(Response::Warning(format!(
+                        "The {} project is already complete.",
+                        project.name
+                    )));
+                }
+                let action = action.unwrap_or_else(|| character.best_action());
+                let rating = character.action(action).map_or(0, |rating| rating.rating);
+                let roll = Roll::new_unsafe(rating).with_kind(RollKind::Fortune);
+                let result = roll.roll(&mut rng);
+                Handler::record_roll(
+                    &transaction,
+                    message,
+                    Some(action.as_str()),
+                    Some(character.name()),
+                    &roll,
+                    &result,
+                );
+                let project = project.tick(result.downtime_ticks());
+                Project::set_ticks(&transaction, channel_id, &project)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                format!(
+                    "rolled {} to work on a long-term project ({}) = {}\n{}{}",
+                    action.as_str(),
+                    roll,
+                    result,
+                    project,
+                    if project.is_complete() {
+                        "\n**Project complete!**"
+                    } else {
+                        ""
+                    }
+                )
+            }
+        };
+
+        Character::use_downtime_activity(&transaction, character.id())
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+        transaction
+            .commit()
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+        Ok(Response::DiceRoll(format!(
+            "took a downtime activity ({}/{}): {}",
+            character.downtime_activities() + 1,
+            MAXIMUM_DOWNTIME_ACTIVITIES,
+            description
+        )))
     }
 
-    fn get_channel(&self, channel_id: ChannelId) -> Channel {
+    /// Record a roll in the channel's roll history.
+    ///
+    /// The dice have already been rolled, so the roll stands even if it can't be recorded, and
+    /// the error is only logged.
+    fn record_roll(
+        connection: &Connection,
+        message: &Message,
+        check: Option<&str>,
+        character: Option<&str>,
+        roll: &Roll,
+        result: &RollResult,
+    ) {
+        if let Err(error) = RollRecord::add(connection, message, check, character, roll, result) {
+            error!(target: "scum-bot", "Error recording roll. Message ID: {}; Error: {:?}", message.id, error);
+        }
+    }
+
+    fn show_stats(&self, user_id: Option<UserId>, session: bool, message: &Message) -> Response {
+        let channel_id = message.channel_id;
         self.pool
             .get()
-            .ok()
-            .and_then(|connection|
-                Channel::get(&connection, channel_id)
-                    .map_err(|error| error!(target: "scum-bot", "Error retrieving channel: Channel ID: {}; Error: {}", channel_id.to_string(), error))
-                    .ok()
-                    .and_then(identity)
-            )
-            .unwrap_or(
-                Channel {
-                    enabled: false,
-                    locked: false,
-                    dice_only: false,
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let started = if session {
+                    Channel::get_session_started(&connection, channel_id)
+                        .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                        .map(Some)
+                        .ok_or_else(|| {
+                            Response::Warning(
+                                "No session has been started in this channel. Type `!session start` at the start of play."
+                                    .to_owned(),
+                            )
+                        })?
+                } else {
+                    None
+                };
+                let stats = RollStats::get(&connection, channel_id, user_id, session)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                let users = if user_id.is_none() && stats.rolls() > 0 {
+                    RollStats::get_users(&connection, channel_id, session)
+                        .and_then(|users| {
+                            users
+                                .into_iter()
+                                .map(|(user_id, user_name)| {
+                                    RollStats::get(&connection, channel_id, Some(user_id), session)
+                                        .map(|stats| (user_name, stats))
+                                })
+                                .collect::<RusqliteResult<Vec<_>>>()
+                        })
+                        .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                } else {
+                    Vec::new()
+                };
+                Ok((started, stats, users))
+            })
+            .map(|(started, stats, users)| {
+                let scope = format!(
+                    "{}{}",
+                    user_id.map_or("this channel", |user_id| {
+                        Handler::mentioned_name(message, user_id).unwrap_or("that player")
+                    }),
+                    started.map_or("".to_owned(), |started| format!(
+                        " since the session started at `{}`",
+                        started
+                    ))
+                );
+                if stats.rolls() == 0 {
+                    Response::DiceRoll(format!("there are no rolls for {}.", scope))
+                } else {
+                    Response::DiceRoll(format!(
+                        "roll statistics for {}:\n{}{}",
+                        scope,
+                        stats,
+                        users.iter().fold(String::new(), |text, (user_name, stats)| format!(
+                            "{}\n• {}: {}",
+                            text,
+                            user_name.as_deref().unwrap_or("Someone"),
+                            stats.summary()
+                        ))
+                    ))
                 }
-            )
+            })
+            .unwrap_or_else(identity)
     }
-}
 
-impl EventHandler for Handler {
-    fn message(&self, ctx: Context, message: Message) {
-        info!(target: "scum-bot", "Received message. Message ID: {}; Content: {}", message.id, message.content.escape_debug());
-        let action = if message.is_own(&ctx.cache) {
-            // Don't respond to our own messages, this may cause an infinite loop
-            Action::IgnoreOwnMessage
-        } else {
-            let channel = self.get_channel(message.channel_id);
-            let is_admin = message.member(&ctx.cache).map_or(true, |member| {
-                member
-                    .permissions(&ctx.cache)
-                    .ok()
-                    .map_or(false, |permissions| permissions.administrator())
-            });
-            let is_private = message.is_private();
-            let command_result = self.get_command(
-                &self.engine,
-                &self.symspell,
-                &message,
-                // Private channels are implicitly dice only, no need to @me
-                channel.dice_only || is_private,
-            );
-            if let Some(command_result) = command_result.as_ref() {
-                match command_result {
-                    Ok(CommandResult::NaturalLanguage(Ok(command), _, corrected)) => {
-                        info!(target: "scum-bot", "Parsed natural language command successfully. Message ID: {}; Command: {:?}; Corrected Message: {}", message.id, command, corrected.as_deref().unwrap_or(""))
-                    }
-                    Ok(CommandResult::NaturalLanguage(Err(error), _, corrected)) => {
-                        info!(target: "scum-bot", "Error parsing natural language command. Message ID: {}; Corrected Message: {}; Error: {:}", message.id, corrected.as_deref().unwrap_or(""), error)
-                    }
-                    Ok(CommandResult::Shorthand(Err(error))) => {
-                        info!(target: "scum-bot", "Error parsing shorthand command. Message ID: {}; Command: {:?}", message.id, error)
-                    }
-                    Ok(CommandResult::Shorthand(Ok(command))) => {
-                        info!(target: "scum-bot", "Parsed shorthand command successfully. Message ID: {}; Command: {:?}", message.id, command)
-                    }
-                    Err(error) => {
-                        info!(target: "scum-bot", "Error parsing command. Message ID: {}; Error: {}", message.id, error)
-                    }
+    fn show_history(&self, filter: &RollFilter, message: &Message) -> Response {
+        let channel_id = message.channel_id;
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                RollRecord::get_recent(&connection, channel_id, filter)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|rolls| {
+                if rolls.is_empty() {
+                    Response::DiceRoll(match filter {
+                        RollFilter::All => "Nobody has rolled in this channel yet.".to_owned(),
+                        RollFilter::User(user_id) => format!(
+                            "{} hasn't rolled in this channel yet.",
+                            Handler::mentioned_name(message, *user_id).unwrap_or("That player")
+                        ),
+                        RollFilter::Check(check) => {
+                            format!("Nobody has rolled {} in this channel yet.", check)
+                        }
+                    })
+                } else {
+                    Response::DiceRoll(rolls.iter().fold(
+                        "Recent rolls, newest first:".to_owned(),
+                        |text, roll| format!("{}\n• {}", text, roll),
+                    ))
                 }
-            };
-            self.get_action(command_result, &channel, &message, is_admin, is_private)
+            })
+            .unwrap_or_else(identity)
+    }
+
+    /// The name of a user mentioned in a message, so that they can be named without being
+    /// mentioned again.
+    fn mentioned_name(message: &Message, user_id: UserId) -> Option<&str> {
+        message
+            .mentions
+            .iter()
+            .find(|user| user.id == user_id)
+            .map(|user| user.name.as_str())
+    }
+
+    fn start_downtime(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Character::reset_downtime_activities(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|_| {
+                Response::CharacterSheet(format!(
+                    "started a downtime phase. Each character may take {} downtime activities, e.g. `!downtime recover`, `!downtime vice`, `!downtime train insight` or `!downtime project \"Fix the drive\" 8`.",
+                    MAXIMUM_DOWNTIME_ACTIVITIES
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn list_bargains(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Bargain::get_outstanding(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|bargains| {
+                if bargains.is_empty() {
+                    Response::Bargain("there are no outstanding devil's bargains.".to_owned())
+                } else {
+                    Response::Bargain(bargains.iter().fold(
+                        "outstanding devil's bargains:".to_owned(),
+                        |text, bargain| format!("{}\n• {}", text, bargain),
+                    ))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn resolve_bargains(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Bargain::resolve_all(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|resolved| {
+                Response::Bargain(format!("resolved {} devil's bargains.", resolved))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn start_group_action(&self, action: ActionName, message: &Message) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| {
+                let group_action = GroupAction::get(&connection, message)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                match group_action {
+                    Some(group_action) if group_action.open => Err(Response::Warning(format!(
+                        "A group {} action led by <@{}> is already underway in this channel. Type `!group done` to resolve it.",
+                        group_action.action.as_str(),
+                        group_action.leader_id
+                    ))),
+                    _ => GroupAction::start(&mut connection, message, action)
+                        .map_err(|error| Response::Error(Error::RusqliteError(error))),
+                }
+            })
+            .map(|_| {
+                Response::DiceRoll(format!(
+                    "is leading a group {} action. Everyone taking part, including the leader, should type `!roll {}` within {} minutes. Then the leader should type `!group done`.",
+                    action.as_str(),
+                    action.as_str().to_lowercase(),
+                    GROUP_ACTION_WINDOW.as_secs() / 60
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn resolve_group_action(&self, message: &Message) -> Response {
+        let channel_id = message.channel_id;
+        let author_id = message.author.id;
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| {
+                let group_action = GroupAction::get(&connection, message)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                    .ok_or_else(|| Response::Warning(NO_GROUP_ACTION_WARNING_TEXT.to_owned()))?;
+                if group_action.leader_id != author_id {
+                    return Err(Response::Warning(format!(
+                        "Only the leader of the group action, <@{}>, can resolve it.",
+                        group_action.leader_id
+                    )));
+                }
+                let best = group_action.best().ok_or_else(|| {
+                    Response::Warning(format!(
+                        "Nobody has rolled for the group action yet. Type `!roll {}` to take part.",
+                        group_action.action.as_str().to_lowercase()
+                    ))
+                })?;
+                let failures = group_action.failures();
+                let stress = Character::get(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                    .map(|leader| {
+                        let stress = leader.apply_stress(failures as i32);
+                        Character::update_stress(&connection, leader.id(), &stress)
+                            .map(|_| stress)
+                            .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    })
+                    .transpose()?;
+                let response = Response::DiceRoll(format!(
+                    "resolved the group {} action with {} participants. The best result was rolled by <@{}> = {}\n{} participants rolled a bad outcome{}.",
+                    group_action.action.as_str(),
+                    group_action.participants.len(),
+                    best.user_id,
+                    best.description,
+                    failures,
+                    stress.map_or("".to_owned(), |stress| format!(", so the leader {}", stress))
+                ));
+                GroupAction::end(&mut connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                Ok(response)
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn assist(&self, user_id: UserId, channel_id: ChannelId, author_id: UserId) -> Response {
+        if user_id == author_id {
+            return Response::Warning("You can't assist yourself.".to_owned());
+        }
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                if let Some(assist) = Assist::get(&connection, channel_id, user_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                {
+                    return Err(Response::Warning(format!(
+                        "<@{}> is already being assisted by <@{}>.",
+                        user_id, assist.assistant_id
+                    )));
+                }
+                let character = Character::get(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+                    .ok_or_else(|| {
+                        Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned())
+                    })?;
+                if character.stress() + ASSIST_STRESS > MAXIMUM_STRESS {
+                    return Err(Response::Warning(format!(
+                        "Your character doesn't have enough stress left to assist. Assisting costs {} stress, and you can't take more than {}.",
+                        ASSIST_STRESS, MAXIMUM_STRESS
+                    )));
+                }
+                let stress = character.apply_stress(ASSIST_STRESS as i32);
+                Character::update_stress(&connection, character.id(), &stress)
+                    .and_then(|_| {
+                        let assist = Assist {
+                            user_id,
+                            assistant_id: author_id,
+                        };
+                        Assist::add(&connection, channel_id, &assist)
+                    })
+                    .map(|_| stress)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|stress| {
+                Response::DiceRoll(format!(
+                    "is assisting <@{}>, giving them +1d on their next action roll, and {}.",
+                    user_id, stress
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn choose_trauma(&self, trauma: Trauma, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| {
+                Character::get(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|character| {
+                        character.ok_or_else(|| {
+                            Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned())
+                        })
+                    })
+                    .and_then(|character| {
+                        if character.pending_trauma() == 0 {
+                            Err(Response::Warning(NO_PENDING_TRAUMA_WARNING_TEXT.to_owned()))
+                        } else if character.traumas().contains(&trauma) {
+                            Err(Response::Warning(format!(
+                                "Your character already suffers the {} trauma. Choose a different trauma.",
+                                trauma.as_str()
+                            )))
+                        } else {
+                            Trauma::add(&mut connection, character.id(), trauma)
+                                .and_then(|_| Character::get(&connection, channel_id, author_id))
+                                .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                        }
+                    })
+                    .and_then(|character| {
+                        character.ok_or_else(|| {
+                            Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned())
+                        })
+                    })
+            })
+            .map(|character| {
+                let message = format!(
+                    "marked the {} trauma ({}/{}).",
+                    trauma.as_str(),
+                    character.traumas().len(),
+                    MAXIMUM_TRAUMAS
+                );
+                if character.must_retire() {
+                    Response::Warning(format!(
+                        "{} Your character has suffered too much trauma, and must retire from the life of scum and villainy.",
+                        message
+                    ))
+                } else {
+                    Response::CharacterSheet(message)
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn create_character(
+        &self,
+        name: &str,
+        playbook: Option<&Playbook>,
+        channel_id: ChannelId,
+        author_id: UserId,
+    ) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| {
+                Character::create(&mut connection, channel_id, author_id, name, playbook)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|inserted| {
+                if inserted > 0 {
+                    Ok(Response::CharacterSheet(match playbook {
+                        Some(playbook) => format!(
+                            "created a new {} named {}. Try typing `!abilities` to see your playbook's special abilities, or `!char show` to see your character sheet.",
+                            playbook.name, name
+                        ),
+                        None => format!(
+                            "created a new character named {}. Try typing `!char set hack 2` to set your action ratings.",
+                            name
+                        ),
+                    }))
+                } else {
+                    Err(Response::Warning(
+                        CHARACTER_ALREADY_EXISTS_WARNING_TEXT.to_owned(),
+                    ))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn use_character(&self, name: &str, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| {
+                Character::set_active(&mut connection, channel_id, author_id, name)
+                    .and_then(|updated| {
+                        if updated > 0 {
+                            Character::get(&connection, channel_id, author_id)
+                        } else {
+                            Ok(None)
+                        }
+                    })
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|character| {
+                character.ok_or_else(|| Response::Warning(Handler::named_character_not_found(name)))
+            })
+            .map(|character| {
+                Response::CharacterSheet(format!("switched to {}.\n{}", character.name(), character))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    /// Whether a channel belongs to a guild, according to the cache.
+    fn is_guild_channel(
+        channel_id: ChannelId,
+        guild_id: Option<GuildId>,
+        cache: &CacheRwLock,
+    ) -> bool {
+        let channel_guild_id = channel_id
+            .to_channel_cached(cache)
+            .and_then(|channel| channel.guild())
+            .map(|channel| channel.read().guild_id);
+        guild_id.is_some() && channel_guild_id == guild_id
+    }
+
+    fn link_campaign(&self, campaign: Campaign, message: &Message) -> Response {
+        let channel_id = message.channel_id;
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| {
+                Campaign::link(&mut connection, channel_id, campaign)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|moved| {
+                Response::CharacterSheet(format!(
+                    "linked this channel to {}, sharing its character sheets{}.",
+                    match campaign {
+                        Campaign::Channel(campaign_channel_id) => {
+                            format!("the campaign of <#{}>", campaign_channel_id)
+                        }
+                        Campaign::Guild(_) => "the server's campaign".to_owned(),
+                    },
+                    if moved > 0 {
+                        format!(
+                            ". {} character{} from this channel moved to the campaign",
+                            moved,
+                            if moved == 1 { "" } else { "s" }
+                        )
+                    } else {
+                        "".to_owned()
+                    }
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn unlink_campaign(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Campaign::unlink(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|deleted| {
+                if deleted > 0 {
+                    Ok(Response::CharacterSheet(
+                        "unlinked this channel from its campaign. Characters created in this channel from now on belong to this channel alone."
+                            .to_owned(),
+                    ))
+                } else {
+                    Err(Response::Warning(
+                        "This channel isn't linked to a campaign.".to_owned(),
+                    ))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn show_campaign(&self, message: &Message) -> Response {
+        let channel_id = message.channel_id;
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let campaign_id = Campaign::get_id(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                Campaign::get_channels(&connection, &campaign_id)
+                    .map(|channels| (campaign_id, channels))
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|(campaign_id, channels)| {
+                let is_guild = message.guild_id.map_or(false, |guild_id| {
+                    Campaign::guild_campaign_id(guild_id) == campaign_id
+                });
+                let mut channels = channels
+                    .iter()
+                    .map(|channel_id| format!("<#{}>", channel_id))
+                    .collect::<Vec<_>>();
+                if !is_guild {
+                    channels.insert(0, format!("<#{}>", campaign_id));
+                }
+                Response::CharacterSheet(format!(
+                    "This channel is part of {}, which spans {}.",
+                    if is_guild {
+                        "the server's campaign".to_owned()
+                    } else {
+                        format!("the campaign of <#{}>", campaign_id)
+                    },
+                    channels.join(", ")
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn list_characters(&self, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Character::get_all(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|characters| {
+                if characters.is_empty() {
+                    Err(Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned()))
+                } else {
+                    Ok(Response::CharacterSheet(format!(
+                        "has the following characters in this channel:{}",
+                        characters
+                            .iter()
+                            .map(|character| format!(
+                                "\n• {}{}{}",
+                                character.name(),
+                                character
+                                    .playbook()
+                                    .map_or("".to_owned(), |playbook| format!(" ({})", playbook.name)),
+                                if character.is_active() { " — active" } else { "" }
+                            ))
+                            .collect::<String>()
+                    )))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn list_abilities(
+        &self,
+        playbook: Option<&Playbook>,
+        channel_id: ChannelId,
+        author_id: UserId,
+    ) -> Response {
+        match playbook {
+            Some(playbook) => Ok(playbook),
+            None => self
+                .pool
+                .get()
+                .map_err(|error| Response::Error(Error::R2D2Error(error)))
+                .and_then(|connection| {
+                    Character::get(&connection, channel_id, author_id)
+                        .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                })
+                .and_then(|character| {
+                    character.as_ref().and_then(Character::playbook).ok_or_else(|| {
+                        Response::Warning(format!(
+                            "Your character doesn't have a playbook. Try typing `!abilities pilot` to see a playbook's special abilities. The playbooks are {}.",
+                            PlaybookList
+                        ))
+                    })
+                }),
+        }
+        .map(|playbook| Response::CharacterSheet(format!("\n{}", playbook)))
+        .unwrap_or_else(identity)
+    }
+
+    fn set_action_rating(
+        &self,
+        action: ActionName,
+        rating: usize,
+        channel_id: ChannelId,
+        author_id: UserId,
+    ) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let character = Handler::get_character(&connection, channel_id, author_id)?;
+                Character::set_action(&connection, character.id(), action, rating)
+                    .and_then(|_| Character::get(&connection, channel_id, author_id))
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|character| {
+                character
+                    .ok_or_else(|| Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned()))
+            })
+            .map(|character| {
+                Response::CharacterSheet(format!(
+                    "set {} to {}.\n{}",
+                    action.as_str(),
+                    rating,
+                    character
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn show_character(&self, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Character::get(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|character| {
+                character
+                    .ok_or_else(|| Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned()))
+            })
+            .map(|character| Response::CharacterSheet(format!("\n{}", character)))
+            .unwrap_or_else(identity)
+    }
+
+    fn get_character(
+        connection: &Connection,
+        channel_id: ChannelId,
+        author_id: UserId,
+    ) -> Result<Character, Response> {
+        Character::get(connection, channel_id, author_id)
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+            .ok_or_else(|| Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned()))
+    }
+
+    fn get_named_character(
+        connection: &Connection,
+        channel_id: ChannelId,
+        author_id: UserId,
+        name: &str,
+    ) -> Result<Character, Response> {
+        Character::get_by_name(connection, channel_id, author_id, name)
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+            .ok_or_else(|| Response::Warning(Handler::named_character_not_found(name)))
+    }
+
+    fn named_character_not_found(name: &str) -> String {
+        format!(
+            "Couldn't find a character of yours named {} in this channel. Try typing `!char list` to see your characters.",
+            name
+        )
+    }
+
+    fn show_load(&self, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| Handler::get_character(&connection, channel_id, author_id))
+            .map(|character| Response::CharacterSheet(format!("\n{}", character.gear())))
+            .unwrap_or_else(identity)
+    }
+
+    fn set_loadout(&self, loadout: Loadout, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let character = Handler::get_character(&connection, channel_id, author_id)?;
+                if character.load() > loadout.limit() {
+                    return Err(Response::Warning(format!(
+                        "You've already used {} load on this job, which is more than a {} load allows.",
+                        character.load(),
+                        loadout.as_str().to_lowercase()
+                    )));
+                }
+                Character::set_loadout(&connection, character.id(), loadout)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                Ok(Response::CharacterSheet(format!(
+                    "chose a {} load, and may use up to {} load of items on this job.",
+                    loadout.as_str().to_lowercase(),
+                    loadout.limit()
+                )))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn add_item(
+        &self,
+        name: &str,
+        load: usize,
+        channel_id: ChannelId,
+        author_id: UserId,
+    ) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let character = Handler::get_character(&connection, channel_id, author_id)?;
+                let inserted = Item::add(&connection, character.id(), name, load)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                if inserted > 0 {
+                    Ok(Response::CharacterSheet(format!(
+                        "added {} (load {}) to their item list.",
+                        name, load
+                    )))
+                } else {
+                    Err(Response::Warning(ITEM_ALREADY_EXISTS_WARNING_TEXT.to_owned()))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn remove_item(&self, name: &str, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let character = Handler::get_character(&connection, channel_id, author_id)?;
+                Item::remove(&connection, character.id(), name)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|deleted| {
+                if deleted > 0 {
+                    Ok(Response::CharacterSheet(format!(
+                        "removed {} from their item list.",
+                        name
+                    )))
+                } else {
+                    Err(Response::Warning(ITEM_NOT_FOUND_WARNING_TEXT.to_owned()))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn use_item(&self, name: &str, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let character = Handler::get_character(&connection, channel_id, author_id)?;
+                let item = character
+                    .item(name)
+                    .ok_or_else(|| Response::Warning(ITEM_NOT_FOUND_WARNING_TEXT.to_owned()))?;
+                if item.used {
+                    return Err(Response::Warning(format!(
+                        "You've already used {} on this job.",
+                        item.name
+                    )));
+                }
+                let loadout = character.loadout().ok_or_else(|| {
+                    Response::Warning(
+                        "You haven't chosen your load for this job. Try typing `!load light`, `!load normal` or `!load heavy`."
+                            .to_owned(),
+                    )
+                })?;
+                let load = character.load() + item.load;
+                if load > loadout.limit() {
+                    return Err(Response::Warning(format!(
+                        "You can't use {} (load {}), as it would take you over your {} load of {}. You've used {} load so far.",
+                        item.name,
+                        item.load,
+                        loadout.as_str().to_lowercase(),
+                        loadout.limit(),
+                        character.load()
+                    )));
+                }
+                Item::set_used(&connection, character.id(), &item.name)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                Ok(Response::CharacterSheet(format!(
+                    "used {} (now {}/{} load).",
+                    item.name,
+                    load,
+                    loadout.limit()
+                )))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn take_harm(
+        &self,
+        level: usize,
+        description: String,
+        channel_id: ChannelId,
+        author_id: UserId,
+    ) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Character::get(&connection, channel_id, author_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|character| {
+                        character.ok_or_else(|| {
+                            Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned())
+                        })
+                    })
+                    .and_then(|character| {
+                        Harm::level_to_take(character.harm(), level)
+                            .map(|taken_level| (character.id(), taken_level))
+                            .ok_or_else(|| Response::Warning(FATAL_HARM_WARNING_TEXT.to_owned()))
+                    })
+                    .and_then(|(character_id, taken_level)| {
+                        let harm = Harm {
+                            level: taken_level,
+                            description,
+                        };
+                        Harm::add(&connection, character_id, &harm)
+                            .map(|_| harm)
+                            .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    })
+            })
+            .map(|harm| {
+                let message = if harm.level > level {
+                    format!(
+                        "took {} harm {}. There were no free level {} harm slots, so the harm was taken at level {}.",
+                        Harm::level_name(harm.level).to_lowercase(),
+                        harm,
+                        level,
+                        harm.level
+                    )
+                } else {
+                    format!(
+                        "took {} harm {}.",
+                        Harm::level_name(harm.level).to_lowercase(),
+                        harm
+                    )
+                };
+                if harm.level >= MAXIMUM_HARM_LEVEL {
+                    Response::Warning(format!("{} This harm is fatal!", message))
+                } else {
+                    Response::CharacterSheet(message)
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn heal_harm(&self, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| {
+                let character = Handler::get_character(&connection, channel_id, author_id)?;
+                let transaction = connection
+                    .transaction()
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                Harm::heal(&transaction, character.id())
+                    .and_then(|_| transaction.commit())
+                    .and_then(|_| Character::get(&connection, channel_id, author_id))
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|character| {
+                character
+                    .ok_or_else(|| Response::Warning(CHARACTER_NOT_FOUND_WARNING_TEXT.to_owned()))
+            })
+            .map(|character| {
+                Response::CharacterSheet(format!(
+                    "healed, reducing each harm by one level.\n{}",
+                    character
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn clear_harm(&self, channel_id: ChannelId, author_id: UserId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let character = Handler::get_character(&connection, channel_id, author_id)?;
+                Harm::clear(&connection, character.id())
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|_| Response::CharacterSheet("cleared all harm.".to_owned()))
+            .unwrap_or_else(identity)
+    }
+
+    fn create_clock(&self, clock: &Clock, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                if let Some(faction) = clock.faction.as_ref() {
+                    Handler::get_faction(&connection, channel_id, faction)?;
+                }
+                Clock::create(&connection, channel_id, clock)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|created| {
+                        if created > 0 {
+                            Ok(Response::Clock(format!("started a new clock: {}", clock)))
+                        } else {
+                            Err(Response::Warning(
+                                CLOCK_ALREADY_EXISTS_WARNING_TEXT.to_owned(),
+                            ))
+                        }
+                    })
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn delete_clock(&self, name: &str, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Clock::delete(&connection, channel_id, name)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|deleted| {
+                if deleted > 0 {
+                    Response::Clock(format!("deleted the {} clock.", name))
+                } else {
+                    Response::Warning(CLOCK_NOT_FOUND_WARNING_TEXT.to_owned())
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn list_clocks(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Clock::get_all(&connection, channel_id)
+                    .and_then(|clocks| {
+                        Project::get_all(&connection, channel_id).map(|projects| (clocks, projects))
+                    })
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|(clocks, projects)| {
+                if clocks.is_empty() && projects.is_empty() {
+                    Response::Clock(
+                        "There are no clocks in this channel. Type `!clock new \"Alarm\" 6` to start one."
+                            .to_owned(),
+                    )
+                } else {
+                    let text = clocks.iter().fold(
+                        "Clocks in this channel:".to_owned(),
+                        |text, clock| format!("{}\n{}", text, clock),
+                    );
+                    if projects.is_empty() {
+                        Response::Clock(text)
+                    } else {
+                        Response::Clock(projects.iter().fold(
+                            format!("{}\nLong-term projects:", text),
+                            |text, project| format!("{}\n{}", text, project),
+                        ))
+                    }
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn tick_clock(&self, name: &str, ticks: usize, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Clock::get(&connection, channel_id, name)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|clock| {
+                        clock.ok_or_else(|| {
+                            Response::Warning(CLOCK_NOT_FOUND_WARNING_TEXT.to_owned())
+                        })
+                    })
+                    .and_then(|clock| {
+                        if clock.is_full() {
+                            return Err(Response::Warning(format!(
+                                "The {} clock is already full.",
+                                clock.name
+                            )));
+                        }
+                        let ticked = clock.tick(ticks);
+                        Clock::set_ticks(&connection, channel_id, &ticked)
+                            .map(|_| ticked)
+                            .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    })
+            })
+            .map(|clock| {
+                if clock.is_full() {
+                    Response::ClockFilled(format!("filled the {} clock!\n{}", clock.name, clock))
+                } else {
+                    Response::Clock(format!("ticked the {} clock.\n{}", clock.name, clock))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn create_ship(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Ship::create(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .and_then(|inserted| {
+                if inserted > 0 {
+                    Ok(Response::CharacterSheet(
+                        "created a new ship for this channel. Try typing `!ship set engines 2` to set its system ratings."
+                            .to_owned(),
+                    ))
+                } else {
+                    Err(Response::Warning(SHIP_ALREADY_EXISTS_WARNING_TEXT.to_owned()))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn show_ship(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| Handler::get_ship(&connection, channel_id))
+            .map(|ship| Response::CharacterSheet(format!("\n{}", ship)))
+            .unwrap_or_else(identity)
+    }
+
+    fn set_ship_system(
+        &self,
+        system: SystemName,
+        rating: usize,
+        channel_id: ChannelId,
+    ) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Ship::set_system(&connection, channel_id, system, rating)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|_| Handler::get_ship(&connection, channel_id))
+            })
+            .map(|ship| {
+                Response::CharacterSheet(format!(
+                    "set {} to {}.\n{}",
+                    system.as_str(),
+                    rating,
+                    ship
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn set_ship_resource(
+        &self,
+        resource: ResourceName,
+        amount: usize,
+        channel_id: ChannelId,
+    ) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Ship::set_resource(&connection, channel_id, resource, amount)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|_| Handler::get_ship(&connection, channel_id))
+            })
+            .map(|ship| {
+                Response::CharacterSheet(format!(
+                    "set {} to {}.\n{}",
+                    resource.as_str(),
+                    amount,
+                    ship
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn add_ship_upgrade(&self, upgrade: &str, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Handler::get_ship(&connection, channel_id)?;
+                Ship::add_upgrade(&connection, channel_id, upgrade)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|added| {
+                        if added > 0 {
+                            Handler::get_ship(&connection, channel_id)
+                        } else {
+                            Err(Response::Warning(format!(
+                                "Your ship already has the {} upgrade.",
+                                upgrade
+                            )))
+                        }
+                    })
+            })
+            .map(|ship| {
+                Response::CharacterSheet(format!("added the {} upgrade.\n{}", upgrade, ship))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn remove_ship_upgrade(&self, upgrade: &str, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Ship::remove_upgrade(&connection, channel_id, upgrade)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|removed| {
+                        if removed > 0 {
+                            Handler::get_ship(&connection, channel_id)
+                        } else {
+                            Err(Response::Warning(format!(
+                                "Your ship doesn't have the {} upgrade.",
+                                upgrade
+                            )))
+                        }
+                    })
+            })
+            .map(|ship| {
+                Response::CharacterSheet(format!("removed the {} upgrade.\n{}", upgrade, ship))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    /// Get the ship for a channel, warning if the channel has no ship.
+    fn get_ship(connection: &Connection, channel_id: ChannelId) -> Result<Ship, Response> {
+        Ship::get(connection, channel_id)
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+            .ok_or_else(|| Response::Warning(SHIP_NOT_FOUND_WARNING_TEXT.to_owned()))
+    }
+
+    fn end_job(&self, job_end: &JobEnd, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let channel = Channel::get(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                let change = HeatChange::apply(
+                    channel.as_ref().map_or(0, |channel| channel.heat),
+                    channel.as_ref().map_or(0, |channel| channel.wanted_level),
+                    job_end.heat(),
+                );
+                Channel::update_heat(&connection, channel_id, &change)
+                    .and_then(|_| Item::reset_all(&connection, channel_id))
+                    .and_then(|_| Character::reset_loadouts(&connection, channel_id))
+                    .map(|_| change)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|change| {
+                Response::Heat(format!(
+                    "ended the job ({}), and the crew {}. Everyone's load and items have been reset.",
+                    job_end, change
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn show_heat(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Channel::get(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|channel| {
+                Response::Heat(format!(
+                    "**Heat** {}/{} **Wanted level** {}/{}",
+                    channel.as_ref().map_or(0, |channel| channel.heat),
+                    MAXIMUM_HEAT,
+                    channel.as_ref().map_or(0, |channel| channel.wanted_level),
+                    MAXIMUM_WANTED_LEVEL
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn add_faction(
+        &self,
+        name: String,
+        tier: usize,
+        hold: Hold,
+        channel_id: ChannelId,
+    ) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let faction = Faction {
+                    name,
+                    tier,
+                    hold,
+                    status: 0,
+                };
+                Faction::create(&connection, channel_id, &faction)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|created| {
+                        if created > 0 {
+                            Ok(Response::CharacterSheet(format!("added a faction: {}", faction)))
+                        } else {
+                            Err(Response::Warning(
+                                FACTION_ALREADY_EXISTS_WARNING_TEXT.to_owned(),
+                            ))
+                        }
+                    })
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn adjust_faction_status(&self, name: &str, amount: i32, channel_id: ChannelId) -> Response {
+        self.update_faction(name, channel_id, |faction| faction.adjust_status(amount))
+            .map(|(previous, faction)| {
+                let message = format!(
+                    "changed the crew's status with {} from {:+} to {:+}.\n{}",
+                    faction.name, previous.status, faction.status, faction
+                );
+                if faction.status != previous.status + amount {
+                    Response::CharacterSheet(format!(
+                        "{}\nFaction status can't go beyond {:+} or {:+}.",
+                        message, -MAXIMUM_STATUS, MAXIMUM_STATUS
+                    ))
+                } else if faction.status == -MAXIMUM_STATUS {
+                    Response::CharacterSheet(format!(
+                        "{}\nThe crew is now **at war** with {}.",
+                        message, faction.name
+                    ))
+                } else {
+                    Response::CharacterSheet(message)
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn set_faction_tier(&self, name: &str, tier: usize, channel_id: ChannelId) -> Response {
+        self.update_faction(name, channel_id, |faction| Faction {
+            tier,
+            ..faction.clone()
+        })
+        .map(|(_, faction)| {
+            Response::CharacterSheet(format!("set the tier of {}.\n{}", faction.name, faction))
+        })
+        .unwrap_or_else(identity)
+    }
+
+    fn set_faction_hold(&self, name: &str, hold: Hold, channel_id: ChannelId) -> Response {
+        self.update_faction(name, channel_id, |faction| Faction {
+            hold,
+            ..faction.clone()
+        })
+        .map(|(_, faction)| {
+            Response::CharacterSheet(format!("set the hold of {}.\n{}", faction.name, faction))
+        })
+        .unwrap_or_else(identity)
+    }
+
+    /// Get a faction, change it and store the result, returning the faction before and after the
+    /// change.
+    fn update_faction<F>(
+        &self,
+        name: &str,
+        channel_id: ChannelId,
+        change: F,
+    ) -> Result<(Faction, Faction), Response>
+    where
+        F: FnOnce(&Faction) -> Faction,
+    {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let previous = Handler::get_faction(&connection, channel_id, name)?;
+                let faction = change(&previous);
+                Faction::update(&connection, channel_id, &faction)
+                    .map(|_| (previous, faction))
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+    }
+
+    fn delete_faction(&self, name: &str, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|mut connection| {
+                Faction::delete(&mut connection, channel_id, name)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|deleted| {
+                if deleted > 0 {
+                    Response::CharacterSheet(format!(
+                        "deleted the {} faction and its clocks.",
+                        name
+                    ))
+                } else {
+                    Response::Warning(FACTION_NOT_FOUND_WARNING_TEXT.to_owned())
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn list_factions(&self, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Faction::get_all(&connection, channel_id)
+                    .and_then(|factions| {
+                        Clock::get_all(&connection, channel_id).map(|clocks| (factions, clocks))
+                    })
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+            })
+            .map(|(factions, clocks)| {
+                if factions.is_empty() {
+                    Response::CharacterSheet(
+                        "There are no factions in this channel. Type `!faction add \"The Hegemony\" 5 strong` to add one."
+                            .to_owned(),
+                    )
+                } else {
+                    Response::CharacterSheet(factions.iter().fold(
+                        "Factions in this channel:".to_owned(),
+                        |text, faction| {
+                            clocks
+                                .iter()
+                                .filter(|clock| {
+                                    clock.faction.as_ref().map_or(false, |name| {
+                                        name.to_lowercase() == faction.name.to_lowercase()
+                                    })
+                                })
+                                .fold(format!("{}\n{}", text, faction), |text, clock| {
+                                    format!("{}\n  • {}", text, clock)
+                                })
+                        },
+                    ))
+                }
+            })
+            .unwrap_or_else(identity)
+    }
+
+    /// Get a faction in a channel by name, warning if there is no such faction.
+    fn get_faction(
+        connection: &Connection,
+        channel_id: ChannelId,
+        name: &str,
+    ) -> Result<Faction, Response> {
+        Faction::get(connection, channel_id, name)
+            .map_err(|error| Response::Error(Error::RusqliteError(error)))?
+            .ok_or_else(|| Response::Warning(FACTION_NOT_FOUND_WARNING_TEXT.to_owned()))
+    }
+
+    fn set_crew_tier(&self, tier: usize, channel_id: ChannelId) -> Response {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                Ship::set_tier(&connection, channel_id, tier)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))
+                    .and_then(|_| Handler::get_ship(&connection, channel_id))
+            })
+            .map(|ship| Response::CharacterSheet(format!("set Tier to {}.\n{}", tier, ship)))
+            .unwrap_or_else(identity)
+    }
+
+    fn help() -> Response {
+        Response::Help(
+            "Try typing the following:\n\
+             • \"Roll three dice\"\n\
+             • \"Do a hacking roll\"\n\
+             • \"Perform an insight resistance roll\"\n\
+             • `!roll 2d8 + 4`, `!roll 4d6kh3` or `!roll 1d20 + 5 with advantage`\n\
+             • `!odds 3d` or `!odds hack push +assist` to see the chances of each outcome\n\
+             • `!history`, `!history @player` or `!history hack` to list recent rolls\n\
+             • `!stats`, `!stats @player` or `!stats session` for roll statistics\n\
+             • `!char create pilot Rin`, `!char set hack 2`, `!char show` or `!abilities`\n\
+             • `!char list`, `!char use Rin` or `!roll hack as Rin` to play several characters\n\
+             • `!campaign link #channel` or `!campaign guild` to share characters across channels\n\
+             • `!fortune 2d`, `!gather 3d` or `!engage bold +1 -1`\n\
+             • `!group sway` to lead a group action, or `!assist @player` to assist\n\
+             • `!clock new \"Alarm\" 6`, `!clock tick Alarm 2` or `!clock list`\n\
+             • `!ship show`, `!ship set engines 2` or `!roll ship engines`\n\
+             • `!job end loud hostile` to take heat at the end of a job, or `!heat`\n\
+             • `!faction status Guilds +1`, `!faction list` or `!engage bold vs Guilds`\n\
+             • `!entanglement` or `!entanglement vs Guilds`\n\
+             • `!downtime start`, then `!downtime recover`, `!downtime vice` or `!downtime train insight`\n\
+             • `!session start`, `!xp insight`, `!advance hack` or `!session end`\n\
+             • `!load normal`, `!item add \"Spacesuit\" 1` or `!use Spacesuit`"
+                .to_owned(),
+        )
+    }
+
+    fn odds(roll: Roll) -> Response {
+        Response::DiceRoll(format!("the odds of rolling {} are:\n{}", roll, roll.odds()))
+    }
+
+    fn roll(&self, roll: Roll, message: &Message) -> Response {
+        let result = self.perform_roll(&roll, message);
+        Response::DiceRoll(format!("rolled {} = {}", roll, result))
+    }
+
+    /// Roll the dice for a roll that isn't made by a character, and record it in the channel's
+    /// roll history.
+    fn perform_roll(&self, roll: &Roll, message: &Message) -> RollResult {
+        let mut rng = rand::thread_rng();
+        let result = roll.roll(&mut rng);
+        match self.pool.get() {
+            Ok(connection) => Handler::record_roll(&connection, message, None, None, roll, &result),
+            Err(error) => {
+                error!(target: "scum-bot", "Error recording roll. Message ID: {}; Error: {:?}", message.id, error)
+            }
+        }
+        result
+    }
+
+    fn roll_expression(expression: &DiceExpression) -> Response {
+        let mut rng = rand::thread_rng();
+        let result = expression.roll(&mut rng);
+        Response::DiceRoll(format!("rolled {} = {}", expression, result))
+    }
+
+    fn engagement(&self, engagement: Engagement, message: &Message) -> Response {
+        self.read_engagement_target(engagement, message.channel_id)
+            .map(|engagement| self.roll_engagement(&engagement, message))
+            .unwrap_or_else(identity)
+    }
+
+    /// Read the difference between the crew's tier and the tier of the faction targeted by an
+    /// engagement roll, if any.
+    fn read_engagement_target(
+        &self,
+        engagement: Engagement,
+        channel_id: ChannelId,
+    ) -> Result<Engagement, Response> {
+        match engagement.target.as_ref() {
+            Some(target) => self
+                .pool
+                .get()
+                .map_err(|error| Response::Error(Error::R2D2Error(error)))
+                .and_then(|connection| {
+                    let faction = Handler::get_faction(&connection, channel_id, target)?;
+                    let ship = Ship::get(&connection, channel_id)
+                        .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                    Ok(Engagement {
+                        tier_difference: faction
+                            .tier_difference(ship.map_or(0, |ship| ship.tier())),
+                        ..engagement.clone()
+                    })
+                }),
+            None => Ok(engagement),
+        }
+    }
+
+    fn roll_engagement(&self, engagement: &Engagement, message: &Message) -> Response {
+        engagement
+            .to_roll()
+            .map_err(|error| {
+                Response::Clarification(format!(
+                    "It looks like you're trying to roll {} dice. {}",
+                    engagement.dice(),
+                    error
+                ))
+            })
+            .map(|roll| {
+                let result = self.perform_roll(&roll, message);
+                Response::DiceRoll(format!("rolled {} ({}) = {}", roll, engagement, result))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn entanglement(&self, entanglement: Entanglement, message: &Message) -> Response {
+        self.read_entanglement_heat(entanglement, message.channel_id)
+            .map(|entanglement| self.roll_entanglement(&entanglement, message))
+            .unwrap_or_else(identity)
+    }
+
+    /// Read the crew's heat and wanted level for an entanglement roll, and the difference between
+    /// the crew's tier and the tier of the faction the entanglement comes from, if any.
+    fn read_entanglement_heat(
+        &self,
+        entanglement: Entanglement,
+        channel_id: ChannelId,
+    ) -> Result<Entanglement, Response> {
+        self.pool
+            .get()
+            .map_err(|error| Response::Error(Error::R2D2Error(error)))
+            .and_then(|connection| {
+                let channel = Channel::get(&connection, channel_id)
+                    .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                let tier_difference = match entanglement.faction.as_ref() {
+                    Some(faction) => {
+                        let faction = Handler::get_faction(&connection, channel_id, faction)?;
+                        let ship = Ship::get(&connection, channel_id)
+                            .map_err(|error| Response::Error(Error::RusqliteError(error)))?;
+                        faction.tier_difference(ship.map_or(0, |ship| ship.tier()))
+                    }
+                    None => 0,
+                };
+                Ok(Entanglement {
+                    heat: channel.as_ref().map_or(0, |channel| channel.heat),
+                    wanted_level: channel.as_ref().map_or(0, |channel| channel.wanted_level),
+                    tier_difference,
+                    ..entanglement
+                })
+            })
+    }
+
+    fn roll_entanglement(&self, entanglement: &Entanglement, message: &Message) -> Response {
+        entanglement
+            .to_roll()
+            .map_err(|error| {
+                Response::Clarification(format!(
+                    "It looks like you're trying to roll {} dice. {}",
+                    entanglement.dice(),
+                    error
+                ))
+            })
+            .map(|roll| {
+                let result = self.perform_roll(&roll, message);
+                Response::DiceRoll(format!(
+                    "rolled {} ({}) = {}\nWith {} heat, look up the entanglement in the **{}** column.",
+                    roll,
+                    entanglement,
+                    result,
+                    entanglement.heat,
+                    entanglement.column()
+                ))
+            })
+            .unwrap_or_else(identity)
+    }
+
+    fn get_channel(&self, channel_id: ChannelId) -> Channel {
+        self.pool
+            .get()
+            .ok()
+            .and_then(|connection|
+                Channel::get(&connection, channel_id)
+                    .map_err(|error| error!(target: "scum-bot", "Error retrieving channel: Channel ID: {}; Error: {}", channel_id.to_string(), error))
+                    .ok()
+                    .and_then(identity)
+            )
+            .unwrap_or(
+                Channel {
+                    enabled: false,
+                    locked: false,
+                    dice_only: false,
+                    heat: 0,
+                    wanted_level: 0,
+                }
+            )
+    }
+}
+
+impl EventHandler for Handler {
+    fn message(&self, ctx: Context, message: Message) {
+        info!(target: "scum-bot", "Received message. Message ID: {}; Content: {}", message.id, message.content.escape_debug());
+        let action = if message.is_own(&ctx.cache) {
+            // Don't respond to our own messages, this may cause an infinite loop
+            Action::IgnoreOwnMessage
+        } else {
+            let channel = self.get_channel(message.channel_id);
+            let is_admin = message.member(&ctx.cache).map_or(true, |member| {
+                member
+                    .permissions(&ctx.cache)
+                    .ok()
+                    .map_or(false, |permissions| permissions.administrator())
+            });
+            let is_private = message.is_private();
+            let command_result = self.get_command(
+                &self.engine,
+                &self.symspell,
+                &message,
+                // Private channels are implicitly dice only, no need to @me
+                channel.dice_only || is_private,
+            );
+            if let Some(command_result) = command_result.as_ref() {
+                match command_result {
+                    Ok(CommandResult::NaturalLanguage(Ok(command), _, corrected)) => {
+                        info!(target: "scum-bot", "Parsed natural language command successfully. Message ID: {}; Command: {:?}; Corrected Message: {}", message.id, command, corrected.as_deref().unwrap_or(""))
+                    }
+                    Ok(CommandResult::NaturalLanguage(Err(error), _, corrected)) => {
+                        info!(target: "scum-bot", "Error parsing natural language command. Message ID: {}; Corrected Message: {}; Error: {:}", message.id, corrected.as_deref().unwrap_or(""), error)
+                    }
+                    Ok(CommandResult::Shorthand(Err(error))) => {
+                        info!(target: "scum-bot", "Error parsing shorthand command. Message ID: {}; Command: {:?}", message.id, error)
+                    }
+                    Ok(CommandResult::Shorthand(Ok(command))) => {
+                        info!(target: "scum-bot", "Parsed shorthand command successfully. Message ID: {}; Command: {:?}", message.id, command)
+                    }
+                    Err(error) => {
+                        info!(target: "scum-bot", "Error parsing command. Message ID: {}; Error: {}", message.id, error)
+                    }
+                }
+            };
+            self.get_action(
+                command_result,
+                &channel,
+                &message,
+                is_admin,
+                is_private,
+                &ctx.cache,
+            )
         };
         match action {
             Action::IgnoreChannelDisabled => {
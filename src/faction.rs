@@ -0,0 +1,203 @@
+use rusqlite::types::{ToSql, Type};
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, Error as RusqliteError, OptionalExtension, Row};
+use serenity::model::id::ChannelId;
+use std::convert::TryInto;
+use std::fmt;
+
+/// The highest tier that a faction, or a crew, may have.
+pub const MAXIMUM_TIER: usize = 6;
+
+/// The most extreme status that a crew may have with a faction, in either direction.
+pub const MAXIMUM_STATUS: i32 = 3;
+
+/// A faction in the setting, such as the Hegemony or the Guilds, tracked per channel.
+///
+/// A faction has a tier and a hold, which measure its power, and a status from -3 (at war) to +3
+/// (allies) which describes its relationship with the crew.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Faction {
+    pub name: String,
+    pub tier: usize,
+    pub hold: Hold,
+    pub status: i32,
+}
+
+impl Faction {
+    /// Get a faction in a channel by name.
+    pub fn get(
+        connection: &Connection,
+        channel_id: ChannelId,
+        name: &str,
+    ) -> RusqliteResult<Option<Faction>> {
+        connection
+            .query_row(
+                "SELECT name, tier, hold, status FROM factions WHERE channel_id = $1 AND name = $2",
+                &[&channel_id.to_string(), name],
+                Faction::from_row,
+            )
+            .optional()
+    }
+
+    /// Get all of the factions in a channel, from the crew's closest allies to their worst
+    /// enemies.
+    pub fn get_all(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<Vec<Faction>> {
+        let mut statement = connection.prepare(
+            "SELECT name, tier, hold, status \
+             FROM factions \
+             WHERE channel_id = $1 \
+             ORDER BY status DESC, rowid",
+        )?;
+        let factions = statement
+            .query_map(&[&channel_id.to_string()], Faction::from_row)?
+            .collect();
+        factions
+    }
+
+    /// Add a faction to a channel, unless a faction with the same name already exists.
+    ///
+    /// Returns the number of factions added.
+    pub fn create(
+        connection: &Connection,
+        channel_id: ChannelId,
+        faction: &Faction,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &channel_id.to_string(),
+            &faction.name,
+            &(faction.tier as i32),
+            &faction.hold.as_str().to_lowercase(),
+            &faction.status,
+        ];
+        connection.execute(
+            "INSERT OR IGNORE INTO factions (channel_id, name, tier, hold, status) VALUES ($1, $2, $3, $4, $5)",
+            params,
+        )
+    }
+
+    /// Store the tier, hold and status of a faction.
+    ///
+    /// Returns the number of factions updated, which is zero if the faction doesn't exist.
+    pub fn update(
+        connection: &Connection,
+        channel_id: ChannelId,
+        faction: &Faction,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &(faction.tier as i32),
+            &faction.hold.as_str().to_lowercase(),
+            &faction.status,
+            &channel_id.to_string(),
+            &faction.name,
+        ];
+        connection.execute(
+            "UPDATE factions \
+             SET tier = $1, hold = $2, status = $3 \
+             WHERE channel_id = $4 \
+             AND name = $5",
+            params,
+        )
+    }
+
+    /// Delete a faction in a channel by name, along with the clocks tracking its projects.
+    ///
+    /// Returns the number of factions deleted.
+    pub fn delete(
+        connection: &mut Connection,
+        channel_id: ChannelId,
+        name: &str,
+    ) -> RusqliteResult<usize> {
+        let channel_id = channel_id.to_string();
+        let transaction = connection.transaction()?;
+        let deleted = transaction.execute(
+            "DELETE FROM factions WHERE channel_id = $1 AND name = $2",
+            &[&channel_id, name],
+        )?;
+        if deleted > 0 {
+            transaction.execute(
+                "DELETE FROM clocks WHERE channel_id = $1 AND faction = $2 COLLATE NOCASE",
+                &[&channel_id, name],
+            )?;
+        }
+        transaction.commit().map(|_| deleted)
+    }
+
+    /// The faction after adjusting the crew's status with it, which can't go beyond -3 or +3.
+    pub fn adjust_status(&self, amount: i32) -> Faction {
+        Faction {
+            status: (self.status + amount)
+                .max(-MAXIMUM_STATUS)
+                .min(MAXIMUM_STATUS),
+            ..self.clone()
+        }
+    }
+
+    /// The difference between a crew's tier and this faction's tier, which is positive if the
+    /// crew has the higher tier.
+    pub fn tier_difference(&self, crew_tier: usize) -> i32 {
+        crew_tier as i32 - self.tier as i32
+    }
+
+    /// The name of the relationship the crew have with the faction at their current status.
+    pub fn status_name(&self) -> &str {
+        match self.status {
+            std::i32::MIN..=-3 => "War",
+            -2 => "Hostile",
+            -1 => "Interfering",
+            0 => "Neutral",
+            1 => "Helpful",
+            2 => "Friendly",
+            _ => "Allies",
+        }
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Faction> {
+        let hold: String = row.get("hold")?;
+        Ok(Faction {
+            name: row.get("name")?,
+            tier: row.get::<_, i32>("tier")?.try_into().unwrap_or(0),
+            hold: Hold::parse(&hold).ok_or_else(|| {
+                RusqliteError::InvalidColumnType(2, "hold".to_owned(), Type::Text)
+            })?,
+            status: row.get("status")?,
+        })
+    }
+}
+
+impl fmt::Display for Faction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "**{}** Tier {} ({} hold), status {:+} ({})",
+            self.name,
+            self.tier,
+            self.hold.as_str().to_lowercase(),
+            self.status,
+            self.status_name()
+        )
+    }
+}
+
+/// How firmly a faction holds on to its power.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Hold {
+    Weak,
+    Strong,
+}
+
+impl Hold {
+    pub fn parse(string: &str) -> Option<Hold> {
+        match string.to_lowercase().as_ref() {
+            "weak" => Some(Hold::Weak),
+            "strong" => Some(Hold::Strong),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Hold::Weak => "Weak",
+            Hold::Strong => "Strong",
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::character::ActionName;
+use crate::roll::{RollOutcome, RollResult};
+use rusqlite::types::{ToSql, Type};
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, Error as RusqliteError, OptionalExtension, Row};
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, UserId};
+use std::time::Duration;
+
+/// How long after a group action starts that other characters may join it.
+pub const GROUP_ACTION_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// A group action, where several characters roll the same action together.
+///
+/// The leader starts the group action, and each participant (including the leader) joins it by
+/// rolling the action. The best result counts for the whole group, but the leader takes one stress
+/// for each participant who rolled a bad outcome.
+///
+/// Group actions are stored with the time that their window closes, so that they survive a restart
+/// of the bot.
+#[derive(Debug)]
+pub struct GroupAction {
+    pub leader_id: UserId,
+    pub action: ActionName,
+    /// Whether characters could still join the group action when it was retrieved.
+    pub open: bool,
+    pub participants: Vec<Participant>,
+}
+
+/// A character who has rolled as part of a group action.
+#[derive(Debug)]
+pub struct Participant {
+    pub user_id: UserId,
+    pub result: i32,
+    pub outcome: RollOutcome,
+    /// The result as it was shown when the participant rolled.
+    pub description: String,
+}
+
+impl GroupAction {
+    /// Get the group action underway in the channel of a message, if any, and whether characters
+    /// may still join it at the time of the message.
+    pub fn get(connection: &Connection, message: &Message) -> RusqliteResult<Option<GroupAction>> {
+        let channel_id = message.channel_id.to_string();
+        let params: &[&dyn ToSql] = &[&channel_id, &message.timestamp];
+        let group_action = connection
+            .query_row(
+                "SELECT leader_id, action, datetime(expires) > datetime($2) AS open \
+                 FROM group_actions \
+                 WHERE channel_id = $1",
+                params,
+                GroupAction::from_row,
+            )
+            .optional()?;
+        match group_action {
+            Some(group_action) => {
+                let mut statement = connection.prepare(
+                    "SELECT user_id, result, outcome, description \
+                     FROM group_action_participants \
+                     WHERE channel_id = $1 \
+                     ORDER BY rowid",
+                )?;
+                let participants = statement
+                    .query_map(&[&channel_id], Participant::from_row)?
+                    .collect::<RusqliteResult<Vec<_>>>()?;
+                Ok(Some(GroupAction {
+                    participants,
+                    ..group_action
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Start a group action led by the author of a message, replacing any previous group action in
+    /// the message's channel.
+    pub fn start(
+        connection: &mut Connection,
+        message: &Message,
+        action: ActionName,
+    ) -> RusqliteResult<usize> {
+        let channel_id = message.channel_id.to_string();
+        let window = format!("+{} seconds", GROUP_ACTION_WINDOW.as_secs());
+        let params: &[&dyn ToSql] = &[
+            &channel_id,
+            &message.author.id.to_string(),
+            &action.as_str(),
+            &message.timestamp,
+            &window,
+        ];
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "DELETE FROM group_action_participants WHERE channel_id = $1",
+            &[&channel_id],
+        )?;
+        let inserted = transaction.execute(
+            "INSERT OR REPLACE INTO group_actions (channel_id, leader_id, action, expires) \
+             VALUES ($1, $2, $3, datetime($4, $5))",
+            params,
+        )?;
+        transaction.commit().map(|_| inserted)
+    }
+
+    /// Add the result of an action roll made by a message to the group action in its channel, if
+    /// the group action is still open, it's for the same action, and the author hasn't already
+    /// rolled for it.
+    ///
+    /// Returns the number of participants added, which is one if the author joined the group
+    /// action, or zero otherwise.
+    pub fn join(
+        connection: &Connection,
+        message: &Message,
+        action: ActionName,
+        result: &RollResult,
+    ) -> RusqliteResult<usize> {
+        let outcome = result.outcome();
+        let params: &[&dyn ToSql] = &[
+            &message.channel_id.to_string(),
+            &message.author.id.to_string(),
+            &result.result(),
+            &outcome.as_str(),
+            &result.to_string(),
+            &action.as_str(),
+            &message.timestamp,
+        ];
+        connection.execute(
+            "INSERT OR IGNORE INTO group_action_participants \
+             (channel_id, user_id, result, outcome, description) \
+             SELECT $1, $2, $3, $4, $5 \
+             FROM group_actions \
+             WHERE channel_id = $1 \
+             AND action = $6 \
+             AND datetime(expires) > datetime($7)",
+            params,
+        )
+    }
+
+    /// End the group action in a channel, removing it along with its participants.
+    pub fn end(connection: &mut Connection, channel_id: ChannelId) -> RusqliteResult<usize> {
+        let channel_id = channel_id.to_string();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "DELETE FROM group_action_participants WHERE channel_id = $1",
+            &[&channel_id],
+        )?;
+        let deleted = transaction.execute(
+            "DELETE FROM group_actions WHERE channel_id = $1",
+            &[&channel_id],
+        )?;
+        transaction.commit().map(|_| deleted)
+    }
+
+    /// The participant with the best result, which counts for the whole group.
+    pub fn best(&self) -> Option<&Participant> {
+        self.participants
+            .iter()
+            .fold(None, |best, participant| match best {
+                Some(best) if !participant.beats(best) => Some(best),
+                _ => Some(participant),
+            })
+    }
+
+    /// The number of participants who rolled a bad outcome, each of whom costs the leader one
+    /// stress.
+    pub fn failures(&self) -> usize {
+        self.participants
+            .iter()
+            .filter(|participant| participant.outcome == RollOutcome::BadOutcome)
+            .count()
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<GroupAction> {
+        let action: String = row.get("action")?;
+        Ok(GroupAction {
+            leader_id: UserId(
+                row.get::<_, String>("leader_id")?
+                    .parse::<u64>()
+                    .unwrap_or(0),
+            ),
+            action: ActionName::parse(&action).ok_or_else(|| {
+                RusqliteError::InvalidColumnType(1, "action".to_owned(), Type::Text)
+            })?,
+            open: row.get("open")?,
+            participants: Vec::new(),
+        })
+    }
+}
+
+impl Participant {
+    /// Whether this participant's result is better than another's.
+    pub fn beats(&self, other: &Participant) -> bool {
+        (self.outcome, self.result) > (other.outcome, other.result)
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Participant> {
+        let outcome: String = row.get("outcome")?;
+        Ok(Participant {
+            user_id: UserId(row.get::<_, String>("user_id")?.parse::<u64>().unwrap_or(0)),
+            result: row.get("result")?,
+            outcome: RollOutcome::parse(&outcome).ok_or_else(|| {
+                RusqliteError::InvalidColumnType(2, "outcome".to_owned(), Type::Text)
+            })?,
+            description: row.get("description")?,
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, Row};
+use std::convert::TryInto;
+use std::fmt;
+
+/// The most severe level of harm, which is fatal.
+pub const MAXIMUM_HARM_LEVEL: usize = 4;
+
+/// An instance of harm suffered by a character, such as "Broken arm".
+///
+/// Harm has a level from 1 (lesser) to 4 (fatal). A character has a limited number of slots for
+/// each level of harm: two at level 1, two at level 2, and one each at levels 3 and 4. Harm that
+/// would not fit in its slots is taken at the next level instead.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Harm {
+    pub level: usize,
+    pub description: String,
+}
+
+impl Harm {
+    /// The number of slots for harm at a level.
+    pub fn slots(level: usize) -> usize {
+        match level {
+            1 | 2 => 2,
+            3 | 4 => 1,
+            _ => 0,
+        }
+    }
+
+    /// The name of a level of harm.
+    pub fn level_name(level: usize) -> &'static str {
+        match level {
+            1 => "Lesser",
+            2 => "Moderate",
+            3 => "Severe",
+            _ => "Fatal",
+        }
+    }
+
+    /// Get the harm that a character suffers, from most to least severe.
+    pub fn get_all(connection: &Connection, character_id: i64) -> RusqliteResult<Vec<Harm>> {
+        let mut statement = connection.prepare(
+            "SELECT level, description \
+             FROM harm \
+             WHERE character_id = $1 \
+             ORDER BY level DESC, rowid",
+        )?;
+        let harm = statement
+            .query_map(&[&character_id], Harm::from_row)?
+            .collect();
+        harm
+    }
+
+    /// Determine the level at which harm will be taken, given the harm a character already
+    /// suffers.
+    ///
+    /// Returns `None` if there is no free slot at or above the level, i.e. the character already
+    /// suffers fatal harm.
+    pub fn level_to_take(existing: &[Harm], level: usize) -> Option<usize> {
+        (level..=MAXIMUM_HARM_LEVEL)
+            .find(|level| existing.iter().filter(|h| h.level == *level).count() < Harm::slots(*level))
+    }
+
+    /// Record harm against a character.
+    pub fn add(connection: &Connection, character_id: i64, harm: &Harm) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&character_id, &(harm.level as i32), &harm.description];
+        connection.execute(
+            "INSERT INTO harm (character_id, level, description) VALUES ($1, $2, $3)",
+            params,
+        )
+    }
+
+    /// Reduce every instance of harm a character suffers by one level, removing lesser harm
+    /// entirely.
+    ///
+    /// This takes two statements, so the caller should run it in a transaction.
+    pub fn heal(connection: &Connection, character_id: i64) -> RusqliteResult<()> {
+        let params = &[&character_id];
+        connection.execute(
+            "DELETE FROM harm WHERE character_id = $1 AND level <= 1",
+            params,
+        )?;
+        connection
+            .execute(
+                "UPDATE harm SET level = level - 1 WHERE character_id = $1",
+                params,
+            )
+            .map(|_| ())
+    }
+
+    /// Remove all harm from a character.
+    pub fn clear(connection: &Connection, character_id: i64) -> RusqliteResult<usize> {
+        connection.execute(
+            "DELETE FROM harm WHERE character_id = $1",
+            &[&character_id],
+        )
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Harm> {
+        Ok(Harm {
+            level: row.get::<_, i32>("level")?.try_into().unwrap_or(0),
+            description: row.get("description")?,
+        })
+    }
+}
+
+impl fmt::Display for Harm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" (level {})", self.description, self.level)
+    }
+}
@@ -0,0 +1,257 @@
+use regex::Regex;
+use std::fmt;
+
+/// The heat at which a crew's wanted level increases.
+pub const MAXIMUM_HEAT: usize = 9;
+
+/// The highest wanted level that a crew may have.
+pub const MAXIMUM_WANTED_LEVEL: usize = 4;
+
+/// How much exposure a job had, which determines the base heat the crew take at the end of it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Exposure {
+    Quiet,
+    Contained,
+    Loud,
+    Wild,
+}
+
+impl Exposure {
+    pub fn parse(string: &str) -> Option<Exposure> {
+        match string.to_lowercase().as_ref() {
+            "quiet" => Some(Exposure::Quiet),
+            "contained" => Some(Exposure::Contained),
+            "loud" => Some(Exposure::Loud),
+            "wild" => Some(Exposure::Wild),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Exposure::Quiet => "quiet",
+            Exposure::Contained => "contained",
+            Exposure::Loud => "loud",
+            Exposure::Wild => "wild",
+        }
+    }
+
+    pub fn heat(&self) -> usize {
+        match self {
+            Exposure::Quiet => 0,
+            Exposure::Contained => 2,
+            Exposure::Loud => 4,
+            Exposure::Wild => 6,
+        }
+    }
+}
+
+/// The exposure factors of a job that has ended, from which the heat the crew take is calculated.
+///
+/// The base heat depends on the job's exposure, with extra heat if the target was high-profile,
+/// the job was on hostile turf, the crew are at war, or killing was involved.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct JobEnd {
+    pub exposure: Exposure,
+    pub high_profile: bool,
+    pub hostile_turf: bool,
+    pub war: bool,
+    pub killing: bool,
+}
+
+impl JobEnd {
+    /// Parse the exposure factors of a job, e.g. `loud hostile killing`.
+    ///
+    /// Returns `None` if the syntax is invalid, or `Some(None)` if no exposure was given.
+    pub fn parse(string: &str) -> Option<Option<JobEnd>> {
+        lazy_static! {
+            static ref FACTOR_REGEX: Regex = Regex::new(
+                r"(?i)^ *(?:(quiet|contained|loud|wild)|(high[- ]profile)|(hostile(?: turf)?)|(war)|(killing))\b"
+            )
+            .unwrap();
+        }
+
+        let mut exposure = None;
+        let mut job_end = JobEnd {
+            exposure: Exposure::Quiet,
+            high_profile: false,
+            hostile_turf: false,
+            war: false,
+            killing: false,
+        };
+        let mut rest = string.trim_end();
+        while !rest.is_empty() {
+            let captures = FACTOR_REGEX.captures(rest)?;
+            if let Some(m) = captures.get(1) {
+                exposure = Exposure::parse(m.as_str());
+            } else if captures.get(2).is_some() {
+                job_end.high_profile = true;
+            } else if captures.get(3).is_some() {
+                job_end.hostile_turf = true;
+            } else if captures.get(4).is_some() {
+                job_end.war = true;
+            } else if captures.get(5).is_some() {
+                job_end.killing = true;
+            }
+            rest = &rest[captures.get(0)?.end()..];
+        }
+        Some(exposure.map(|exposure| JobEnd {
+            exposure,
+            ..job_end
+        }))
+    }
+
+    /// The total heat the crew take for this job.
+    pub fn heat(&self) -> usize {
+        self.exposure.heat()
+            + self.high_profile as usize
+            + self.hostile_turf as usize
+            + self.war as usize
+            + 2 * self.killing as usize
+    }
+}
+
+impl fmt::Display for JobEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} from a {} job",
+            self.exposure.heat(),
+            self.exposure.as_str()
+        )?;
+        if self.high_profile {
+            write!(f, ", +1 high-profile target")?;
+        }
+        if self.hostile_turf {
+            write!(f, ", +1 hostile turf")?;
+        }
+        if self.war {
+            write!(f, ", +1 at war")?;
+        }
+        if self.killing {
+            write!(f, ", +2 killing")?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of a crew taking heat.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HeatChange {
+    /// The amount of heat taken.
+    pub amount: usize,
+    /// The crew's heat after the change.
+    pub heat: usize,
+    /// The crew's wanted level after the change.
+    pub wanted_level: usize,
+    /// The number of wanted levels gained because the crew's heat overflowed.
+    pub wanted_levels_gained: usize,
+}
+
+impl HeatChange {
+    /// Calculate the result of a crew with some heat and wanted level taking an amount of heat.
+    ///
+    /// Whenever heat reaches the maximum the crew's wanted level increases, and the excess heat
+    /// carries over.
+    pub fn apply(heat: usize, wanted_level: usize, amount: usize) -> HeatChange {
+        let total = heat + amount;
+        let wanted_levels_gained =
+            (total / MAXIMUM_HEAT).min(MAXIMUM_WANTED_LEVEL.saturating_sub(wanted_level));
+        HeatChange {
+            amount,
+            heat: (total - wanted_levels_gained * MAXIMUM_HEAT).min(MAXIMUM_HEAT),
+            wanted_level: wanted_level + wanted_levels_gained,
+            wanted_levels_gained,
+        }
+    }
+}
+
+impl fmt::Display for HeatChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "took {} heat (now {}/{})", self.amount, self.heat, MAXIMUM_HEAT)?;
+        if self.wanted_levels_gained > 0 {
+            write!(
+                f,
+                ". Heat overflowed, and the crew's **wanted level rises** to {}/{}",
+                self.wanted_level, MAXIMUM_WANTED_LEVEL
+            )?;
+        } else {
+            write!(
+                f,
+                ". Wanted level {}/{}",
+                self.wanted_level, MAXIMUM_WANTED_LEVEL
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_distinguishes_no_exposure_from_invalid_syntax() {
+        assert_eq!(JobEnd::parse(""), Some(None));
+        assert_eq!(JobEnd::parse("hostile"), Some(None));
+        assert_eq!(JobEnd::parse("bogus"), None);
+        assert_eq!(JobEnd::parse("loud bogus"), None);
+    }
+
+    #[test]
+    fn parse_reads_every_factor() {
+        let job_end = JobEnd::parse("loud high-profile hostile turf war killing")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            job_end,
+            JobEnd {
+                exposure: Exposure::Loud,
+                high_profile: true,
+                hostile_turf: true,
+                war: true,
+                killing: true,
+            }
+        );
+        assert_eq!(job_end.heat(), 9);
+    }
+
+    #[test]
+    fn excess_heat_carries_over_to_the_next_wanted_level() {
+        assert_eq!(
+            HeatChange::apply(8, 0, 3),
+            HeatChange {
+                amount: 3,
+                heat: 2,
+                wanted_level: 1,
+                wanted_levels_gained: 1,
+            }
+        );
+        assert_eq!(HeatChange::apply(5, 1, 4).heat, 0);
+        assert_eq!(HeatChange::apply(5, 1, 4).wanted_level, 2);
+        assert_eq!(HeatChange::apply(4, 0, 15).wanted_levels_gained, 2);
+        assert_eq!(HeatChange::apply(4, 0, 15).heat, 1);
+    }
+
+    #[test]
+    fn heat_stops_at_the_maximum_wanted_level() {
+        assert_eq!(
+            HeatChange::apply(5, MAXIMUM_WANTED_LEVEL, 4),
+            HeatChange {
+                amount: 4,
+                heat: MAXIMUM_HEAT,
+                wanted_level: MAXIMUM_WANTED_LEVEL,
+                wanted_levels_gained: 0,
+            }
+        );
+        assert_eq!(
+            HeatChange::apply(8, MAXIMUM_WANTED_LEVEL, 3).heat,
+            MAXIMUM_HEAT
+        );
+        assert_eq!(
+            HeatChange::apply(8, 3, 12).wanted_level,
+            MAXIMUM_WANTED_LEVEL
+        );
+        assert_eq!(HeatChange::apply(8, 3, 12).heat, MAXIMUM_HEAT);
+    }
+}
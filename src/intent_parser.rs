@@ -1,7 +1,9 @@
 use crate::character::{AttributeName, ActionName};
-use crate::character_roll::{CharacterRoll, Check};
+use crate::character_roll::{ActionOptions, CharacterRoll, Check};
 use crate::command::{Command, Error};
-use crate::roll::Roll;
+use crate::engagement::Engagement;
+use crate::roll::{Effect, Position, Roll, RollKind, RollMode};
+use crate::trauma::Trauma;
 use snips_nlu_ontology::{IntentParserResult, Slot, SlotValue};
 use std::convert::TryFrom;
 
@@ -12,21 +14,50 @@ pub fn parse_intent_result(result: &IntentParserResult) -> Result<Command, Error
         .as_ref()
         .ok_or(Error::NoIntent)
         .and_then(|intent_name| match intent_name.as_ref() {
+            "chooseTrauma" => parse_choose_trauma(&slots),
+            "clearHarm" => Ok(Command::ClearHarm),
+            "createCharacter" => Ok(Command::CreateCharacter(None, None)),
+            "healHarm" => Ok(Command::HealHarm),
             "rollAction" => parse_roll_action(&slots),
-            "rollDice" => parse_roll_dice(&slots),
+            "rollDice" => parse_roll_dice(&slots, RollKind::Action),
+            "rollEngagement" => parse_roll_engagement(&slots),
+            "rollFortune" => parse_roll_dice(&slots, RollKind::Fortune),
+            "rollGatherInformation" => parse_roll_dice(&slots, RollKind::GatherInformation),
             "rollResistance" => parse_roll_resistance(&slots),
+            "setActionRating" => parse_set_action_rating(&slots),
+            "showCharacter" => Ok(Command::ShowCharacter),
             "showHelp" => Ok(Command::Help),
+            "takeHarm" => parse_take_harm(&slots),
             intent_name => Err(Error::UnknownIntent(intent_name.to_owned())),
         })
 }
 
-fn parse_roll_dice(slots: &[Slot]) -> Result<Command, Error> {
+fn parse_choose_trauma(slots: &[Slot]) -> Result<Command, Error> {
+    extract_custom_slot_value(slots, "trauma")
+        .and_then(|value| Trauma::parse(value.as_ref()))
+        .map(Command::ChooseTrauma)
+        .ok_or(Error::ChooseTraumaMissingTrauma)
+}
+
+fn parse_roll_dice(slots: &[Slot], kind: RollKind) -> Result<Command, Error> {
     let rolls = extract_usize_slot_value(slots, "rolls").unwrap_or(1);
+    let mode = extract_custom_slot_value(slots, "mode")
+        .and_then(|value| RollMode::parse(value.as_ref()))
+        .unwrap_or(RollMode::Normal);
     Roll::new(rolls)
-        .map(Command::Roll)
+        .map(|roll| Command::Roll(roll.with_kind(kind).with_mode(mode)))
         .map_err(|error| Error::RollDiceInvalid(error, rolls))
 }
 
+fn parse_roll_engagement(slots: &[Slot]) -> Result<Command, Error> {
+    Ok(Command::Engagement(Engagement {
+        bold: find_slot_by_name(slots, "plan").is_some(),
+        advantages: extract_usize_slot_value(slots, "advantages").unwrap_or(0),
+        complications: extract_usize_slot_value(slots, "complications").unwrap_or(0),
+        ..Engagement::default()
+    }))
+}
+
 fn parse_roll_resistance(slots: &[Slot]) -> Result<Command, Error> {
     let attribute = extract_attribute_slot(slots);
     attribute
@@ -34,6 +65,7 @@ fn parse_roll_resistance(slots: &[Slot]) -> Result<Command, Error> {
         .map(|attribute| {
             let roll = CharacterRoll {
                 check: Check::Attribute(attribute),
+                character: None,
             };
             Command::CharacterRoll(roll)
         })
@@ -41,15 +73,40 @@ fn parse_roll_resistance(slots: &[Slot]) -> Result<Command, Error> {
 
 fn parse_roll_action(slots: &[Slot]) -> Result<Command, Error> {
     let action = extract_action_slot(slots);
-    let bonus = extract_usize_slot_value(slots, "bonus").unwrap_or(0);
+    let options = ActionOptions {
+        bonus: extract_usize_slot_value(slots, "bonus").unwrap_or(0),
+        push: find_slot_by_name(slots, "push").is_some(),
+        position: extract_custom_slot_value(slots, "position")
+            .and_then(|value| Position::parse(value.as_ref())),
+        effect: extract_custom_slot_value(slots, "effect")
+            .and_then(|value| Effect::parse(value.as_ref())),
+        assisted_by: None,
+        bargain: extract_custom_slot_value(slots, "bargain").cloned(),
+        gambit: false,
+    };
     action.ok_or(Error::RollActionMissingAction).map(|action| {
         let roll = CharacterRoll {
-            check: Check::Action(action, bonus),
+            check: Check::Action(action, options),
+            character: None,
         };
         Command::CharacterRoll(roll)
     })
 }
 
+fn parse_set_action_rating(slots: &[Slot]) -> Result<Command, Error> {
+    let action = extract_action_slot(slots).ok_or(Error::SetActionRatingMissingAction)?;
+    let rating =
+        extract_usize_slot_value(slots, "rating").ok_or(Error::SetActionRatingMissingRating)?;
+    Command::set_action_rating(action, rating)
+}
+
+fn parse_take_harm(slots: &[Slot]) -> Result<Command, Error> {
+    let level = extract_usize_slot_value(slots, "level").ok_or(Error::TakeHarmMissingLevel)?;
+    let description = extract_custom_slot_value(slots, "description")
+        .ok_or(Error::TakeHarmMissingDescription)?;
+    Command::take_harm(level, description.to_owned())
+}
+
 fn extract_attribute_slot(slots: &[Slot]) -> Option<AttributeName> {
     extract_custom_slot_value(slots, "attribute").and_then(|value| AttributeName::parse(value.as_ref()))
 }
@@ -0,0 +1,149 @@
+use crate::campaign::CAMPAIGN_ID;
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, Row};
+use serenity::model::id::ChannelId;
+use std::convert::TryInto;
+use std::fmt;
+
+/// An item that a character may carry on a job, such as "Spacesuit".
+///
+/// Items are declared as they're used during a job, and the total load of the items used may not
+/// exceed the load the character chose at the start of the job.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Item {
+    pub name: String,
+    pub load: usize,
+    pub used: bool,
+}
+
+impl Item {
+    /// Get the items on a character's item list, in the order they were added.
+    pub fn get_all(connection: &Connection, character_id: i64) -> RusqliteResult<Vec<Item>> {
+        let mut statement = connection.prepare(
+            "SELECT name, load, used \
+             FROM items \
+             WHERE character_id = $1 \
+             ORDER BY rowid",
+        )?;
+        let items = statement
+            .query_map(&[&character_id], Item::from_row)?
+            .collect();
+        items
+    }
+
+    /// Add an item to a character's item list.
+    ///
+    /// Returns the number of rows inserted, which is zero if the character already has an item
+    /// with that name.
+    pub fn add(
+        connection: &Connection,
+        character_id: i64,
+        name: &str,
+        load: usize,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&character_id, &name, &(load as i32)];
+        connection.execute(
+            "INSERT OR IGNORE INTO items (character_id, name, load) VALUES ($1, $2, $3)",
+            params,
+        )
+    }
+
+    /// Remove an item from a character's item list.
+    ///
+    /// Returns the number of rows deleted, which is zero if the character has no item with that
+    /// name.
+    pub fn remove(connection: &Connection, character_id: i64, name: &str) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&character_id, &name];
+        connection.execute(
+            "DELETE FROM items WHERE character_id = $1 AND name = $2",
+            params,
+        )
+    }
+
+    /// Mark an item as used on the current job.
+    ///
+    /// Returns the number of rows updated, which is zero if the character has no item with that
+    /// name.
+    pub fn set_used(
+        connection: &Connection,
+        character_id: i64,
+        name: &str,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&character_id, &name];
+        connection.execute(
+            "UPDATE items SET used = 1 WHERE character_id = $1 AND name = $2",
+            params,
+        )
+    }
+
+    /// Mark every item of every character in the campaign that a channel belongs to as unused, at
+    /// the end of a job.
+    pub fn reset_all(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<usize> {
+        connection.execute(
+            &format!(
+                "UPDATE items \
+                 SET used = 0 \
+                 WHERE character_id IN (SELECT character_id FROM characters WHERE campaign_id = {})",
+                CAMPAIGN_ID
+            ),
+            &[&channel_id.to_string()],
+        )
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Item> {
+        Ok(Item {
+            name: row.get("name")?,
+            load: row.get::<_, i32>("load")?.try_into().unwrap_or(0),
+            used: row.get("used")?,
+        })
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} (load {})",
+            if self.used { "☑" } else { "☐" },
+            self.name,
+            self.load
+        )
+    }
+}
+
+/// The load that a character chooses to carry at the start of a job.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Loadout {
+    Light,
+    Normal,
+    Heavy,
+}
+
+impl Loadout {
+    pub fn parse(string: &str) -> Option<Loadout> {
+        match string.to_lowercase().as_ref() {
+            "light" => Some(Loadout::Light),
+            "normal" => Some(Loadout::Normal),
+            "heavy" => Some(Loadout::Heavy),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Loadout::Light => "Light",
+            Loadout::Normal => "Normal",
+            Loadout::Heavy => "Heavy",
+        }
+    }
+
+    /// The total load of items that may be used with this loadout.
+    pub fn limit(&self) -> usize {
+        match self {
+            Loadout::Light => 3,
+            Loadout::Normal => 5,
+            Loadout::Heavy => 6,
+        }
+    }
+}
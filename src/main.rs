@@ -3,16 +3,35 @@ extern crate lazy_static;
 extern crate log;
 extern crate symspell;
 
+mod assist;
+mod bargain;
+mod campaign;
 mod channel;
 mod character;
 mod character_roll;
+mod clock;
 mod command;
+mod dice_expression;
+mod downtime;
+mod engagement;
+mod entanglement;
 mod error;
 mod event_handler;
+mod faction;
+mod group_action;
+mod harm;
+mod heat;
 mod intent_logger;
 mod intent_parser;
+mod item;
+mod playbook;
+mod project;
 mod response;
 mod roll;
+mod roll_history;
+mod roll_stats;
+mod ship;
+mod trauma;
 
 use crate::event_handler::Handler;
 use log::error;
@@ -0,0 +1,91 @@
+use crate::character::ActionName;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+lazy_static! {
+    /// The playbooks, loaded from the data files in `config/playbooks`.
+    static ref PLAYBOOKS: Vec<Playbook> = [
+        include_str!("../config/playbooks/mechanic.json"),
+        include_str!("../config/playbooks/muscle.json"),
+        include_str!("../config/playbooks/mystic.json"),
+        include_str!("../config/playbooks/pilot.json"),
+        include_str!("../config/playbooks/scoundrel.json"),
+        include_str!("../config/playbooks/speaker.json"),
+        include_str!("../config/playbooks/stitch.json"),
+    ]
+    .iter()
+    .map(|data| serde_json::from_str(data).expect("Invalid playbook data file"))
+    .collect();
+}
+
+/// A playbook, such as Pilot or Mechanic, from which a character is created.
+///
+/// A playbook gives a new character their starting action ratings, and has a list of special
+/// abilities and an XP trigger that is checked at the end of each session.
+#[derive(Debug, Deserialize)]
+pub struct Playbook {
+    pub name: String,
+    pub description: String,
+    pub xp_trigger: String,
+    action_ratings: BTreeMap<String, usize>,
+    pub abilities: Vec<Ability>,
+}
+
+/// A special ability that a character from a playbook may have.
+#[derive(Debug, Deserialize)]
+pub struct Ability {
+    pub name: String,
+    pub description: String,
+}
+
+impl Playbook {
+    /// Every playbook, in alphabetical order.
+    pub fn all() -> &'static [Playbook] {
+        &PLAYBOOKS
+    }
+
+    /// Find a playbook by name, ignoring case.
+    pub fn get(name: &str) -> Option<&'static Playbook> {
+        let name = name.trim();
+        PLAYBOOKS
+            .iter()
+            .find(|playbook| playbook.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The rating a new character from this playbook starts with in an action.
+    pub fn action_rating(&self, action: ActionName) -> usize {
+        self.action_ratings
+            .iter()
+            .find(|(name, _)| ActionName::parse(name) == Some(action))
+            .map_or(0, |(_, rating)| *rating)
+    }
+}
+
+impl fmt::Display for Playbook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "**{}**: {}", self.name, self.description)?;
+        write!(f, "\n**XP trigger** {}", self.xp_trigger)?;
+        for ability in self.abilities.iter() {
+            write!(f, "\n• **{}**: {}", ability.name, ability.description)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the list of playbooks as shorthand command examples.
+pub struct PlaybookList;
+
+impl fmt::Display for PlaybookList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, playbook) in Playbook::all().iter().enumerate() {
+            write!(
+                f,
+                "{}`{}`",
+                if index > 0 { ", " } else { "" },
+                playbook.name.to_lowercase()
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use crate::clock::Clock;
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, OptionalExtension, Row};
+use serenity::model::id::ChannelId;
+use std::convert::TryInto;
+use std::fmt;
+
+/// A long-term project, such as "Fix the drive", which characters work on during downtime.
+///
+/// Each project has its own clock. Projects are named per channel, and names are matched
+/// case-insensitively. They're stored apart from the clocks started with `!clock`, so that working
+/// on a project can't tick a GM's or a faction's clock.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Project {
+    pub name: String,
+    pub segments: usize,
+    pub ticks: usize,
+}
+
+impl Project {
+    /// Get a project in a channel by name.
+    pub fn get(
+        connection: &Connection,
+        channel_id: ChannelId,
+        name: &str,
+    ) -> RusqliteResult<Option<Project>> {
+        connection
+            .query_row(
+                "SELECT name, segments, ticks FROM projects WHERE channel_id = $1 AND name = $2",
+                &[&channel_id.to_string(), name],
+                Project::from_row,
+            )
+            .optional()
+    }
+
+    /// Get all of the projects in a channel, in the order they were started.
+    pub fn get_all(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<Vec<Project>> {
+        let mut statement = connection.prepare(
+            "SELECT name, segments, ticks FROM projects WHERE channel_id = $1 ORDER BY rowid",
+        )?;
+        let projects = statement
+            .query_map(&[&channel_id.to_string()], Project::from_row)?
+            .collect();
+        projects
+    }
+
+    /// Start a project in a channel, unless a project with the same name already exists.
+    ///
+    /// Returns the number of projects created.
+    pub fn create(
+        connection: &Connection,
+        channel_id: ChannelId,
+        project: &Project,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &channel_id.to_string(),
+            &project.name,
+            &(project.segments as i32),
+            &(project.ticks as i32),
+        ];
+        connection.execute(
+            "INSERT OR IGNORE INTO projects (channel_id, name, segments, ticks) VALUES ($1, $2, $3, $4)",
+            params,
+        )
+    }
+
+    /// Store the number of ticks on a project's clock.
+    pub fn set_ticks(
+        connection: &Connection,
+        channel_id: ChannelId,
+        project: &Project,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[
+            &(project.ticks as i32),
+            &channel_id.to_string(),
+            &project.name,
+        ];
+        connection.execute(
+            "UPDATE projects SET ticks = $1 WHERE channel_id = $2 AND name = $3",
+            params,
+        )
+    }
+
+    /// The project after ticking a number of segments on its clock, stopping once it is full.
+    pub fn tick(&self, ticks: usize) -> Project {
+        Project {
+            ticks: (self.ticks + ticks).min(self.segments),
+            ..self.clone()
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.ticks >= self.segments
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Project> {
+        Ok(Project {
+            name: row.get("name")?,
+            segments: row.get::<_, i32>("segments")?.try_into().unwrap_or(0),
+            ticks: row.get::<_, i32>("ticks")?.try_into().unwrap_or(0),
+        })
+    }
+}
+
+impl fmt::Display for Project {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Clock {
+            name: self.name.clone(),
+            segments: self.segments,
+            ticks: self.ticks,
+            faction: None,
+        }
+        .fmt(f)
+    }
+}
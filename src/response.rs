@@ -2,9 +2,14 @@ use crate::error::Error;
 use serenity::model::id::{MessageId, UserId};
 
 pub enum Response {
+    Bargain(String),
+    CharacterSheet(String),
     Clarification(String),
+    Clock(String),
+    ClockFilled(String),
     DiceRoll(String),
     Error(Error),
+    Heat(String),
     Help(String),
     Warning(String),
 }
@@ -12,12 +17,19 @@ pub enum Response {
 impl Response {
     pub fn render(&self, author_id: UserId, message_id: MessageId) -> String {
         match self {
+            Response::Bargain(message) => format!("😈 <@{}> {}", author_id, message),
+            Response::CharacterSheet(message) => format!("📜 <@{}> {}", author_id, message),
             Response::Clarification(message) => format!("📎 <@{}> {}", author_id, message),
+            Response::Clock(message) => format!("🕰️ <@{}> {}", author_id, message),
+            Response::ClockFilled(message) => {
+                format!("🚨 <@{}> **Clock filled:** {}", author_id, message)
+            }
             Response::DiceRoll(message) => format!("🎲 <@{}> {}", author_id, message),
             Response::Error(_) => format!(
                 "💥 <@{}> **Error:** A technical error has occurred. Reference ID: {}",
                 author_id, message_id
             ),
+            Response::Heat(message) => format!("🔥 <@{}> {}", author_id, message),
             Response::Help(message) => format!("🎱 <@{}> {}", author_id, message),
             Response::Warning(message) => format!("⚠️ <@{}> {}", author_id, message),
         }
@@ -25,15 +25,43 @@ impl fmt::Display for RollOperation {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum RollOutcome {
-    CriticalSuccess,
-    FullSuccess,
-    PartialSuccess,
+/// The outcome of a roll, ordered from worst to best.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum RollOutcome {
     BadOutcome,
+    PartialSuccess,
+    FullSuccess,
+    CriticalSuccess,
 }
 
 impl RollOutcome {
+    /// Every outcome, from best to worst.
+    pub const ALL: [RollOutcome; 4] = [
+        RollOutcome::CriticalSuccess,
+        RollOutcome::FullSuccess,
+        RollOutcome::PartialSuccess,
+        RollOutcome::BadOutcome,
+    ];
+
+    pub fn parse(string: &str) -> Option<RollOutcome> {
+        match string {
+            "critical" => Some(RollOutcome::CriticalSuccess),
+            "full" => Some(RollOutcome::FullSuccess),
+            "partial" => Some(RollOutcome::PartialSuccess),
+            "bad" => Some(RollOutcome::BadOutcome),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            RollOutcome::CriticalSuccess => "critical",
+            RollOutcome::FullSuccess => "full",
+            RollOutcome::PartialSuccess => "partial",
+            RollOutcome::BadOutcome => "bad",
+        }
+    }
+
     fn from_result(result: i32, critical: bool) -> RollOutcome {
         if critical {
             RollOutcome::CriticalSuccess
@@ -45,42 +73,451 @@ impl RollOutcome {
             RollOutcome::BadOutcome
         }
     }
+
+    /// The exact chance of each possible result of rolling a number of dice once, ordered from
+    /// worst to best as compared by `RollResult::beats`.
+    ///
+    /// Rolling dice is resolved by the highest die and the number of sixes, so the chances are
+    /// worked out one die at a time over those two values, rather than over every combination of
+    /// dice. Rolling zero dice is resolved by the lower of two dice, and can't be critical.
+    fn chances(rolls: usize) -> Vec<((RollOutcome, i32), f64)> {
+        let mut chances: Vec<((RollOutcome, i32), f64)> = if rolls > 0 {
+            // The chance of each highest die so far, indexed by `[result][sixes]`, where a result
+            // of zero means no dice have been rolled yet and `sixes` counts up to two.
+            let mut highest = [[0.0; 3]; 7];
+            highest[0][0] = 1.0;
+            for _ in 0..rolls {
+                let mut next = [[0.0; 3]; 7];
+                for (result, sixes_chances) in highest.iter().enumerate() {
+                    for (sixes, chance) in sixes_chances.iter().enumerate() {
+                        for die in 1..=6 {
+                            let sixes = if die == 6 { (sixes + 1).min(2) } else { sixes };
+                            next[result.max(die)][sixes] += chance / 6.0;
+                        }
+                    }
+                }
+                highest = next;
+            }
+            highest
+                .iter()
+                .enumerate()
+                .skip(1)
+                .flat_map(|(result, sixes_chances)| {
+                    let result = result as i32;
+                    sixes_chances.iter().enumerate().map(move |(sixes, chance)| {
+                        ((RollOutcome::from_result(result, sixes > 1), result), *chance)
+                    })
+                })
+                .collect()
+        } else {
+            (1..=6)
+                .map(|result| {
+                    let higher = (7 - result) * (7 - result) - (6 - result) * (6 - result);
+                    ((RollOutcome::from_result(result, false), result), f64::from(higher) / 36.0)
+                })
+                .collect()
+        };
+        chances.sort_by_key(|(key, _)| *key);
+        chances.dedup_by(|(key, chance), (previous_key, previous_chance)| {
+            key == previous_key && {
+                *previous_chance += *chance;
+                true
+            }
+        });
+        chances
+    }
+}
+
+impl RollOutcome {
+    /// The name of this outcome in the vocabulary of a kind of roll.
+    pub fn name(&self, kind: RollKind) -> &str {
+        match (kind, self) {
+            (RollKind::Action, RollOutcome::CriticalSuccess) => "Critical Success",
+            (RollKind::Action, RollOutcome::FullSuccess) => "Full Success",
+            (RollKind::Action, RollOutcome::PartialSuccess) => "Partial Success",
+            (RollKind::Action, RollOutcome::BadOutcome) => "Bad Outcome",
+            (RollKind::Fortune, RollOutcome::CriticalSuccess) => "Great",
+            (RollKind::Fortune, RollOutcome::FullSuccess) => "Standard",
+            (RollKind::Fortune, RollOutcome::PartialSuccess) => "Limited",
+            (RollKind::Fortune, RollOutcome::BadOutcome) => "Poor",
+            (RollKind::GatherInformation, RollOutcome::CriticalSuccess) => "Exceptional Information",
+            (RollKind::GatherInformation, RollOutcome::FullSuccess) => "Great Information",
+            (RollKind::GatherInformation, RollOutcome::PartialSuccess) => "Standard Information",
+            (RollKind::GatherInformation, RollOutcome::BadOutcome) => "Limited Information",
+            (RollKind::Engagement, RollOutcome::CriticalSuccess)
+            | (RollKind::Engagement, RollOutcome::FullSuccess) => "Controlled Position",
+            (RollKind::Engagement, RollOutcome::PartialSuccess) => "Risky Position",
+            (RollKind::Engagement, RollOutcome::BadOutcome) => "Desperate Position",
+            (RollKind::Entanglement, RollOutcome::CriticalSuccess)
+            | (RollKind::Entanglement, RollOutcome::FullSuccess) => "Row 6",
+            (RollKind::Entanglement, RollOutcome::PartialSuccess) => "Row 4/5",
+            (RollKind::Entanglement, RollOutcome::BadOutcome) => "Row 1-3",
+        }
+    }
+
+    /// A description of this outcome for kinds of roll other than action rolls.
+    fn description(&self, kind: RollKind) -> Option<&str> {
+        match (kind, self) {
+            (RollKind::Action, _) => None,
+            (RollKind::Fortune, RollOutcome::CriticalSuccess) => Some("an exceptional result"),
+            (RollKind::Fortune, RollOutcome::FullSuccess) => Some("a good result"),
+            (RollKind::Fortune, RollOutcome::PartialSuccess) => Some("a mixed result"),
+            (RollKind::Fortune, RollOutcome::BadOutcome) => Some("a bad result"),
+            (RollKind::GatherInformation, RollOutcome::CriticalSuccess) => Some("exceptional details, more than you asked for"),
+            (RollKind::GatherInformation, RollOutcome::FullSuccess) => Some("you get what you want to know"),
+            (RollKind::GatherInformation, RollOutcome::PartialSuccess) => Some("good information, but it may be incomplete"),
+            (RollKind::GatherInformation, RollOutcome::BadOutcome) => Some("partial or incomplete information"),
+            (RollKind::Engagement, RollOutcome::CriticalSuccess) => Some("you've already overcome the first obstacle"),
+            (RollKind::Engagement, _) => None,
+            (RollKind::Entanglement, _) => None,
+        }
+    }
+
+    pub fn emoji(&self) -> &str {
+        match self {
+            RollOutcome::CriticalSuccess => "🤩",
+            RollOutcome::FullSuccess => "😄",
+            RollOutcome::PartialSuccess => "😑",
+            RollOutcome::BadOutcome => "😰",
+        }
+    }
+
+    /// The consequences of this outcome for an action roll made at a position.
+    fn consequences(&self, position: Position) -> &str {
+        match (self, position) {
+            (RollOutcome::CriticalSuccess, _) => "do it with increased effect",
+            (RollOutcome::FullSuccess, _) => "do it",
+            (RollOutcome::PartialSuccess, Position::Controlled) => "hesitate; withdraw and try a different approach, or do it with a minor consequence",
+            (RollOutcome::PartialSuccess, Position::Risky) => "do it, but suffer harm, a complication, reduced effect, or end up in a desperate position",
+            (RollOutcome::PartialSuccess, Position::Desperate) => "do it, but suffer severe harm, a serious complication, or reduced effect",
+            (RollOutcome::BadOutcome, Position::Controlled) => "falter; press on by seizing a risky opportunity, or withdraw and try a different approach",
+            (RollOutcome::BadOutcome, Position::Risky) => "things go badly; suffer harm, a complication, end up in a desperate position, or lose this opportunity",
+            (RollOutcome::BadOutcome, Position::Desperate) => "the worst outcome; suffer severe harm, a serious complication, or lose this opportunity",
+        }
+    }
+
+    /// Write the outcome in the vocabulary of a kind of roll, tailored to the position and effect
+    /// of an action roll if known.
+    fn fmt_with(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        kind: RollKind,
+        position: Option<Position>,
+        effect: Option<Effect>,
+    ) -> fmt::Result {
+        write!(f, " — {}", self.name(kind))?;
+        if let Some(position) = position {
+            write!(f, " at {} {}: {}", position, self.emoji(), self.consequences(position))?;
+        } else if let Some(description) = self.description(kind) {
+            write!(f, " {}: {}", self.emoji(), description)?;
+        } else {
+            write!(f, " {}", self.emoji())?;
+        }
+        match (self, effect) {
+            (RollOutcome::CriticalSuccess, Some(effect)) => {
+                write!(f, " ({} effect, increased from {})", effect.increased(), effect)
+            }
+            (RollOutcome::FullSuccess, Some(effect))
+            | (RollOutcome::PartialSuccess, Some(effect)) => write!(f, " ({} effect)", effect),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl fmt::Display for RollOutcome {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, RollKind::Action, None, None)
+    }
+}
+
+/// The exact chance of each outcome of a roll, as worked out before rolling it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Odds {
+    kind: RollKind,
+    /// The chance of each outcome, in the order of `RollOutcome::ALL`.
+    chances: [f64; 4],
+}
+
+impl Odds {
+    /// The chance of an outcome, from zero to one.
+    pub fn chance(&self, outcome: RollOutcome) -> f64 {
+        RollOutcome::ALL
+            .iter()
+            .position(|other| *other == outcome)
+            .map_or(0.0, |index| self.chances[index])
+    }
+}
+
+impl fmt::Display for Odds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "```")?;
+        for (outcome, chance) in RollOutcome::ALL.iter().zip(self.chances.iter()) {
+            write!(f, "\n{:<24}{:>6.1}%", outcome.name(self.kind), chance * 100.0)?;
+        }
+        write!(f, "\n```")
+    }
+}
+
+/// The kind of a dice roll, which determines the vocabulary used to describe its outcome.
+///
+/// All kinds of roll are resolved the same way, by the highest die rolled, but the outcome means
+/// something different for each.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RollKind {
+    /// An action roll, or a plain roll of a dice pool.
+    Action,
+    /// A fortune roll, to determine the outcome of an uncertain situation.
+    Fortune,
+    /// A roll to gather information.
+    GatherInformation,
+    /// An engagement roll, to determine the starting position of a job.
+    Engagement,
+    /// An entanglement roll, to determine the trouble the crew face after a job.
+    Entanglement,
+}
+
+impl RollKind {
+    pub fn parse(string: &str) -> Option<RollKind> {
+        match string {
+            "action" => Some(RollKind::Action),
+            "fortune" => Some(RollKind::Fortune),
+            "gather_information" => Some(RollKind::GatherInformation),
+            "engagement" => Some(RollKind::Engagement),
+            "entanglement" => Some(RollKind::Entanglement),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            RollKind::Action => "action",
+            RollKind::Fortune => "fortune",
+            RollKind::GatherInformation => "gather_information",
+            RollKind::Engagement => "engagement",
+            RollKind::Entanglement => "entanglement",
+        }
+    }
+
+    fn suffix(&self) -> &str {
         match self {
-            RollOutcome::CriticalSuccess => write!(f, " — Critical Success 🤩"),
-            RollOutcome::FullSuccess => write!(f, " — Full Success 😄"),
-            RollOutcome::PartialSuccess => write!(f, " — Partial Success 😑"),
-            RollOutcome::BadOutcome => write!(f, " — Bad Outcome 😰"),
+            RollKind::Action => "",
+            RollKind::Fortune => " fortune roll",
+            RollKind::GatherInformation => " gather information roll",
+            RollKind::Engagement => " engagement roll",
+            RollKind::Entanglement => " entanglement roll",
         }
     }
 }
 
+/// The position of an action roll, which determines how severe the consequences are.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Position {
+    Controlled,
+    Risky,
+    Desperate,
+}
+
+impl Position {
+    pub fn parse(string: &str) -> Option<Position> {
+        match string.to_lowercase().as_ref() {
+            "controlled" => Some(Position::Controlled),
+            "risky" => Some(Position::Risky),
+            "desperate" => Some(Position::Desperate),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Position::Controlled => "Controlled",
+            Position::Risky => "Risky",
+            Position::Desperate => "Desperate",
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Whether a roll is made once, or twice keeping the better or worse result.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RollMode {
+    Normal,
+    Advantage,
+    Disadvantage,
+}
+
+impl RollMode {
+    pub fn parse(string: &str) -> Option<RollMode> {
+        match string.to_lowercase().as_ref() {
+            "normal" => Some(RollMode::Normal),
+            "advantage" | "adv" => Some(RollMode::Advantage),
+            "disadvantage" | "dis" => Some(RollMode::Disadvantage),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            RollMode::Normal => "normal",
+            RollMode::Advantage => "advantage",
+            RollMode::Disadvantage => "disadvantage",
+        }
+    }
+}
+
+impl fmt::Display for RollMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// The effect of an action roll, which determines how much is accomplished by it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Effect {
+    Limited,
+    Standard,
+    Great,
+    Extreme,
+}
+
+impl Effect {
+    pub fn parse(string: &str) -> Option<Effect> {
+        match string.to_lowercase().as_ref() {
+            "limited" => Some(Effect::Limited),
+            "standard" => Some(Effect::Standard),
+            "great" => Some(Effect::Great),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Effect::Limited => "Limited",
+            Effect::Standard => "Standard",
+            Effect::Great => "Great",
+            Effect::Extreme => "Extreme",
+        }
+    }
+
+    /// The effect after being increased by one level, as on a critical success.
+    pub fn increased(&self) -> Effect {
+        match self {
+            Effect::Limited => Effect::Standard,
+            Effect::Standard => Effect::Great,
+            Effect::Great | Effect::Extreme => Effect::Extreme,
+        }
+    }
+
+    /// The effect after being reduced by one level, as by lesser harm.
+    pub fn reduced(&self) -> Effect {
+        match self {
+            Effect::Limited | Effect::Standard => Effect::Limited,
+            Effect::Great => Effect::Standard,
+            Effect::Extreme => Effect::Great,
+        }
+    }
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
 /// A dice roll that might occur in Scum and Villainy.
 ///
 /// A dice roll involves rolling a number of six-sided dice. The highest value die rolled
-/// determines the outcome of the roll.
+/// determines the outcome of the roll, which is described according to the kind of roll. Action
+/// rolls may also have a position and effect, which determine the consequences of the outcome. A
+/// roll with advantage or disadvantage is made twice, keeping the better or worse result.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Roll {
     rolls: usize,
+    kind: RollKind,
+    position: Option<Position>,
+    effect: Option<Effect>,
+    mode: RollMode,
 }
 
 /// The detailed result of a dice roll.
 ///
 /// In addition to the numerical result itself, it includes the individual die values, and whether
-/// the roll was a critical success, so that this information can be presented to the user.
-#[derive(Debug, Eq, PartialEq)]
+/// the roll was a critical success, so that this information can be presented to the user. For a
+/// roll with advantage or disadvantage, it also includes the result that wasn't kept.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RollResult {
     result: i32,
     operation: RollOperation,
     dice: Vec<i32>,
     outcome: RollOutcome,
+    kind: RollKind,
+    position: Option<Position>,
+    effect: Option<Effect>,
+    discarded: Option<(RollMode, Box<RollResult>)>,
 }
 
-impl fmt::Display for RollResult {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl RollResult {
+    /// Whether this result is better than another, by its outcome and then by its highest die.
+    pub fn beats(&self, other: &RollResult) -> bool {
+        (self.outcome, self.result) > (other.outcome, other.result)
+    }
+
+    pub fn outcome(&self) -> RollOutcome {
+        self.outcome
+    }
+
+    /// The individual dice rolled for the kept result.
+    pub fn dice(&self) -> &[i32] {
+        &self.dice
+    }
+
+    /// Whether this result earns the crew a gambit, which happens when a risky action roll yields
+    /// a 6. Action rolls without a position are risky, which is the default position.
+    ///
+    /// This doesn't account for whether a gambit was spent on the roll, which prevents earning
+    /// one.
+    pub fn earns_gambit(&self) -> bool {
+        self.kind == RollKind::Action
+            && self.position.unwrap_or(Position::Risky) == Position::Risky
+            && self.operation == RollOperation::Max
+            && self.dice.contains(&6)
+    }
+
+    /// The highest die rolled, or for a roll of zero dice, the lowest of the two dice rolled.
+    pub fn result(&self) -> i32 {
+        self.result
+    }
+
+    /// The number of segments that a downtime roll with this result ticks on a clock.
+    pub fn downtime_ticks(&self) -> usize {
+        match self.outcome {
+            RollOutcome::CriticalSuccess => 5,
+            RollOutcome::FullSuccess => 3,
+            RollOutcome::PartialSuccess => 2,
+            RollOutcome::BadOutcome => 1,
+        }
+    }
+
+    /// The stress cost of a resistance roll with this result.
+    ///
+    /// Resisting costs six stress minus the highest die rolled, while a critical success instead
+    /// clears one stress.
+    pub fn resistance_stress(&self) -> i32 {
+        if self.outcome == RollOutcome::CriticalSuccess {
+            -1
+        } else {
+            6 - self.result
+        }
+    }
+}
+
+impl RollResult {
+    /// Write the highest die rolled, and the individual dice if there are more than one.
+    fn fmt_dice(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "**{}**", self.result).and({
             if self.dice.len() > 1 {
                 let mut iter = self.dice.iter().take(MAXIMUM_ROLLS_DISPLAY);
@@ -99,7 +536,21 @@ impl fmt::Display for RollResult {
                 Ok(())
             }
         })
-        .and(self.outcome.fmt(f))
+    }
+}
+
+impl fmt::Display for RollResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_dice(f)
+            .and(match &self.discarded {
+                Some((mode, discarded)) => {
+                    write!(f, ", with {} over ~~", mode)
+                        .and(discarded.fmt_dice(f))
+                        .and(write!(f, "~~"))
+                }
+                None => Ok(()),
+            })
+            .and(self.outcome.fmt_with(f, self.kind, self.position, self.effect))
     }
 }
 
@@ -156,13 +607,63 @@ impl Roll {
     pub const fn new_unsafe(rolls: usize) -> Roll {
         Roll {
             rolls,
+            kind: RollKind::Action,
+            position: None,
+            effect: None,
+            mode: RollMode::Normal,
         }
     }
 
-    /// Parse a roll from a String using conventional Scum and Villainy syntax.
+    /// Make this roll a different kind of roll, changing how its outcome is described.
+    pub fn with_kind(self, kind: RollKind) -> Roll {
+        Roll { kind, ..self }
+    }
+
+    /// Make this roll at a position, tailoring the consequences of its outcome.
+    pub fn with_position(self, position: Option<Position>) -> Roll {
+        Roll { position, ..self }
+    }
+
+    /// Make this roll with an effect, reporting the effect achieved by its outcome.
+    pub fn with_effect(self, effect: Option<Effect>) -> Roll {
+        Roll { effect, ..self }
+    }
+
+    /// The number of dice in the pool.
+    pub fn rolls(&self) -> usize {
+        self.rolls
+    }
+
+    pub fn kind(&self) -> RollKind {
+        self.kind
+    }
+
+    pub fn mode(&self) -> RollMode {
+        self.mode
+    }
+
+    /// Add dice to this roll, validating that the number of dice being rolled are still no more
+    /// than the maximum allowed value.
+    pub fn with_extra_dice(self, dice: usize) -> Result<Roll, Error> {
+        Roll::new(self.rolls + dice).map(|roll| Roll {
+            rolls: roll.rolls,
+            ..self
+        })
+    }
+
+    /// Make this roll with advantage or disadvantage, rolling twice and keeping the better or
+    /// worse result.
+    pub fn with_mode(self, mode: RollMode) -> Roll {
+        Roll { mode, ..self }
+    }
+
+    /// Parse a roll from a String using conventional Scum and Villainy syntax, optionally followed
+    /// by `with advantage` or `with disadvantage`.
     pub fn parse(string: &str) -> Result<Roll, ParserError> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"^(\d+)d$").unwrap();
+            static ref RE: Regex = Regex::new(
+                r"(?i)^(\d+)d(?:\s+(?:with\s+)?(advantage|disadvantage|adv|dis))?$"
+            ).unwrap();
         }
         Roll::parse_regex(&RE, string)
     }
@@ -171,17 +672,78 @@ impl Roll {
         regex
             .captures(string)
             .and_then(|captures| {
-                captures
+                let rolls = captures
                     .get(1)
-                    .and_then(|m| m.as_str().parse::<usize>().ok())
+                    .and_then(|m| m.as_str().parse::<usize>().ok())?;
+                let mode = captures
+                    .get(2)
+                    .and_then(|m| RollMode::parse(m.as_str()))
+                    .unwrap_or(RollMode::Normal);
+                Some((rolls, mode))
             })
             .ok_or(ParserError::InvalidSyntax)
-            .and_then(|rolls| {
-                Roll::new(rolls).map_err(ParserError::InvalidValue)
+            .and_then(|(rolls, mode)| {
+                Roll::new(rolls)
+                    .map(|roll| roll.with_mode(mode))
+                    .map_err(ParserError::InvalidValue)
             })
     }
 
+    /// Work out the exact chance of each outcome of this roll, before rolling it.
+    ///
+    /// With advantage or disadvantage, the better or worse of two results is kept, so the chance
+    /// of keeping a result is worked out from the chances of rolling no better and no worse.
+    pub fn odds(&self) -> Odds {
+        let mut chances = [0.0; 4];
+        let mut worse = 0.0;
+        for ((outcome, _), chance) in RollOutcome::chances(self.rolls) {
+            let no_better = worse + chance;
+            let kept = match self.mode {
+                RollMode::Normal => chance,
+                RollMode::Advantage => no_better * no_better - worse * worse,
+                RollMode::Disadvantage => {
+                    (1.0 - worse) * (1.0 - worse) - (1.0 - no_better) * (1.0 - no_better)
+                }
+            };
+            let index = RollOutcome::ALL
+                .iter()
+                .position(|other| *other == outcome)
+                .unwrap_or(0);
+            chances[index] += kept;
+            worse = no_better;
+        }
+        Odds {
+            kind: self.kind,
+            chances,
+        }
+    }
+
+    /// Roll the dice, rolling them a second time if the roll has advantage or disadvantage.
     pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> RollResult {
+        let result = self.roll_once(rng);
+        match self.mode {
+            RollMode::Normal => result,
+            mode => {
+                let other = self.roll_once(rng);
+                let keep_other = if mode == RollMode::Advantage {
+                    other.beats(&result)
+                } else {
+                    result.beats(&other)
+                };
+                let (kept, discarded) = if keep_other {
+                    (other, result)
+                } else {
+                    (result, other)
+                };
+                RollResult {
+                    discarded: Some((mode, Box::new(discarded))),
+                    ..kept
+                }
+            }
+        }
+    }
+
+    fn roll_once<R: Rng + ?Sized>(&self, rng: &mut R) -> RollResult {
         if self.rolls > 0 {
             let dice = Roll::roll_once_component(self.rolls, rng);
             let result = *(dice.iter().max().unwrap_or(&1));
@@ -192,6 +754,10 @@ impl Roll {
                 operation: RollOperation::Max,
                 dice,
                 outcome,
+                kind: self.kind,
+                position: self.position,
+                effect: self.effect,
+                discarded: None,
             }
         } else {
             let dice = Roll::roll_once_component(2, rng);
@@ -202,6 +768,10 @@ impl Roll {
                 operation: RollOperation::Min,
                 dice,
                 outcome,
+                kind: self.kind,
+                position: self.position,
+                effect: self.effect,
+                discarded: None,
             }
         }
     }
@@ -216,6 +786,120 @@ impl Roll {
 
 impl fmt::Display for Roll {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}d", self.rolls)
+        write!(f, "{}d{}", self.rolls, self.kind.suffix()).and(match self.mode {
+            RollMode::Normal => Ok(()),
+            mode => write!(f, " with {}", mode),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const EPSILON: f64 = 1e-12;
+
+    /// Every way of rolling a number of dice, each equally likely.
+    fn every_roll(dice: usize) -> Vec<Vec<i32>> {
+        (0..dice).fold(vec![Vec::new()], |rolls, _| {
+            rolls
+                .iter()
+                .flat_map(|roll| {
+                    (1..=6).map(move |die| {
+                        let mut roll = roll.clone();
+                        roll.push(die);
+                        roll
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// The result of a roll of a dice pool, worked out directly from the dice.
+    fn result_of(rolls: usize, dice: &[i32]) -> (RollOutcome, i32) {
+        if rolls > 0 {
+            let result = *dice.iter().max().unwrap();
+            let critical = dice.iter().filter(|die| **die == 6).count() > 1;
+            (RollOutcome::from_result(result, critical), result)
+        } else {
+            let result = *dice.iter().min().unwrap();
+            (RollOutcome::from_result(result, false), result)
+        }
+    }
+
+    /// The chance of each result of a dice pool, by counting every way of rolling it.
+    fn brute_force_chances(rolls: usize) -> BTreeMap<(RollOutcome, i32), f64> {
+        // Rolling zero dice is resolved by rolling two dice instead.
+        let every_roll = every_roll(if rolls > 0 { rolls } else { 2 });
+        let each = 1.0 / every_roll.len() as f64;
+        let mut chances = BTreeMap::new();
+        for dice in every_roll.iter() {
+            *chances.entry(result_of(rolls, dice)).or_insert(0.0) += each;
+        }
+        chances
+    }
+
+    #[test]
+    fn chances_match_every_roll() {
+        for rolls in 0..=4 {
+            let expected = brute_force_chances(rolls);
+            let chances: BTreeMap<_, _> = RollOutcome::chances(rolls).into_iter().collect();
+            for key in expected.keys().chain(chances.keys()) {
+                let chance = chances.get(key).cloned().unwrap_or(0.0);
+                let expected = expected.get(key).cloned().unwrap_or(0.0);
+                assert!(
+                    (chance - expected).abs() < EPSILON,
+                    "{} dice: {:?} has chance {}, expected {}",
+                    rolls,
+                    key,
+                    chance,
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn odds_match_every_roll() {
+        for rolls in 0..=4 {
+            let results = brute_force_chances(rolls);
+            for mode in [
+                RollMode::Normal,
+                RollMode::Advantage,
+                RollMode::Disadvantage,
+            ]
+            .iter()
+            {
+                let mut expected = BTreeMap::new();
+                for (first, first_chance) in results.iter() {
+                    if *mode == RollMode::Normal {
+                        *expected.entry(first.0).or_insert(0.0) += first_chance;
+                        continue;
+                    }
+                    for (second, second_chance) in results.iter() {
+                        // Results are compared in the same order as `RollResult::beats`.
+                        let kept = match mode {
+                            RollMode::Advantage => first.max(second),
+                            _ => first.min(second),
+                        };
+                        *expected.entry(kept.0).or_insert(0.0) += first_chance * second_chance;
+                    }
+                }
+                let odds = Roll::new(rolls).unwrap().with_mode(*mode).odds();
+                for outcome in RollOutcome::ALL.iter() {
+                    let expected = expected.get(outcome).cloned().unwrap_or(0.0);
+                    assert!(
+                        (odds.chance(*outcome) - expected).abs() < EPSILON,
+                        "{} dice with {:?}: {:?} has chance {}, expected {}",
+                        rolls,
+                        mode,
+                        outcome,
+                        odds.chance(*outcome),
+                        expected
+                    );
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,166 @@
+use crate::roll::{Roll, RollKind, RollMode, RollOutcome, RollResult, MAXIMUM_ROLLS_DISPLAY};
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, Row};
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, UserId};
+use std::fmt;
+
+/// The maximum number of rolls listed from a channel's roll history at a time.
+pub const MAXIMUM_HISTORY: usize = 10;
+
+/// Which rolls to list from a channel's roll history.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RollFilter {
+    All,
+    User(UserId),
+    Check(String),
+}
+
+/// A roll made in a channel, recorded against the message that made it.
+///
+/// Every roll of a dice pool is recorded, so that players can look back over recent rolls and the
+/// GM has an audit trail when a result is disputed. Rolls of dice expressions aren't recorded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RollRecord {
+    pub user_id: UserId,
+    /// The name of the user when they rolled, which is shown instead of mentioning them.
+    pub user_name: Option<String>,
+    pub character: Option<String>,
+    pub check: Option<String>,
+    pub roll: Roll,
+    pub dice: Vec<i32>,
+    pub result: i32,
+    pub outcome: RollOutcome,
+    pub posted: String,
+}
+
+impl RollRecord {
+    /// Record the result of a roll made by a message, with the check and character it was made
+    /// for, if any.
+    pub fn add(
+        connection: &Connection,
+        message: &Message,
+        check: Option<&str>,
+        character: Option<&str>,
+        roll: &Roll,
+        result: &RollResult,
+    ) -> RusqliteResult<usize> {
+        let dice = result
+            .dice()
+            .iter()
+            .map(|die| die.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let (kind, mode, outcome) = (roll.kind(), roll.mode(), result.outcome());
+        let params: &[&dyn ToSql] = &[
+            &message.id.to_string(),
+            &message.channel_id.to_string(),
+            &message.author.id.to_string(),
+            &message.author.name,
+            &character,
+            &check,
+            &kind.as_str(),
+            &(roll.rolls() as i32),
+            &mode.as_str(),
+            &dice,
+            &result.result(),
+            &outcome.as_str(),
+            &message.timestamp,
+        ];
+        connection.execute(
+            "INSERT INTO rolls (message_id, channel_id, user_id, user_name, character, check_name, kind, pool, mode, dice, result, outcome, posted) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+            params,
+        )
+    }
+
+    /// Get the most recent rolls made in a channel that match a filter, from newest to oldest.
+    pub fn get_recent(
+        connection: &Connection,
+        channel_id: ChannelId,
+        filter: &RollFilter,
+    ) -> RusqliteResult<Vec<RollRecord>> {
+        let channel_id = channel_id.to_string();
+        let (condition, value) = match filter {
+            RollFilter::All => ("", None),
+            RollFilter::User(user_id) => ("AND user_id = $2", Some(user_id.to_string())),
+            RollFilter::Check(check) => ("AND check_name = $2", Some(check.to_owned())),
+        };
+        let mut params: Vec<&dyn ToSql> = vec![&channel_id];
+        if let Some(value) = value.as_ref() {
+            params.push(value);
+        }
+        let mut statement = connection.prepare(&format!(
+            "SELECT user_id, user_name, character, check_name, kind, pool, mode, dice, result, outcome, \
+             strftime('%Y-%m-%d %H:%M', posted) AS posted \
+             FROM rolls \
+             WHERE channel_id = $1 \
+             {} \
+             ORDER BY rolls.posted DESC, roll_id DESC \
+             LIMIT {}",
+            condition, MAXIMUM_HISTORY
+        ))?;
+        let rolls = statement
+            .query_map(&params, RollRecord::from_row)?
+            .collect();
+        rolls
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<RollRecord> {
+        let kind = RollKind::parse(&row.get::<_, String>("kind")?).unwrap_or(RollKind::Action);
+        let mode = RollMode::parse(&row.get::<_, String>("mode")?).unwrap_or(RollMode::Normal);
+        let pool = row.get::<_, i32>("pool")?.max(0) as usize;
+        Ok(RollRecord {
+            user_id: UserId(row.get::<_, String>("user_id")?.parse::<u64>().unwrap_or(0)),
+            user_name: row.get("user_name")?,
+            character: row.get("character")?,
+            check: row.get("check_name")?,
+            roll: Roll::new_unsafe(pool).with_kind(kind).with_mode(mode),
+            dice: row
+                .get::<_, String>("dice")?
+                .split(',')
+                .filter_map(|die| die.parse::<i32>().ok())
+                .collect(),
+            result: row.get("result")?,
+            outcome: RollOutcome::parse(&row.get::<_, String>("outcome")?)
+                .unwrap_or(RollOutcome::BadOutcome),
+            posted: row.get("posted")?,
+        })
+    }
+}
+
+impl fmt::Display for RollRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` {} rolled ",
+            self.posted,
+            self.user_name.as_deref().unwrap_or("Someone")
+        )?;
+        match (self.check.as_ref(), self.character.as_ref()) {
+            (Some(check), Some(character)) => {
+                write!(f, "{} as {} ({})", check, character, self.roll)
+            }
+            (Some(check), None) => write!(f, "{} ({})", check, self.roll),
+            (None, _) => write!(f, "{}", self.roll),
+        }?;
+        write!(f, " = **{}**", self.result)?;
+        if self.dice.len() > 1 {
+            let operation = if self.roll.rolls() > 0 { "max" } else { "min" };
+            write!(f, " = {}(", operation)?;
+            for (index, die) in self.dice.iter().take(MAXIMUM_ROLLS_DISPLAY).enumerate() {
+                write!(f, "{}{}", if index > 0 { ", " } else { "" }, die)?;
+            }
+            if self.dice.len() > MAXIMUM_ROLLS_DISPLAY {
+                write!(f, ", …")?;
+            }
+            write!(f, ")")?;
+        }
+        write!(
+            f,
+            " — {} {}",
+            self.outcome.name(self.roll.kind()),
+            self.outcome.emoji()
+        )
+    }
+}
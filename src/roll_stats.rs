@@ -0,0 +1,226 @@
+use crate::roll::{Roll, RollKind, RollMode, RollOutcome};
+use rusqlite::types::ToSql;
+use rusqlite::Connection;
+use rusqlite::Result as RusqliteResult;
+use serenity::model::id::{ChannelId, UserId};
+use std::convert::TryInto;
+use std::fmt;
+
+/// The maximum number of most used actions listed in roll statistics.
+pub const MAXIMUM_STATS_CHECKS: usize = 3;
+
+/// An SQL condition selecting the rolls made in a channel, given by the first parameter, by a user,
+/// given by the second parameter if not null, and if the third parameter is true, since the
+/// channel's current session started.
+const CONDITION: &str = "channel_id = $1 \
+     AND ($2 IS NULL OR user_id = $2) \
+     AND (NOT $3 OR datetime(posted) >= \
+     (SELECT datetime(session_started) FROM channels WHERE channel_id = $1))";
+
+/// Statistics about the rolls recorded in a channel's roll history, either for the whole channel
+/// or for a single user.
+///
+/// Alongside the outcomes actually rolled, it includes the number of each outcome expected from
+/// the dice pools that were rolled, so that players can see whether the dice have been kind.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RollStats {
+    /// The number of rolls with each outcome, in the order of `RollOutcome::ALL`.
+    outcomes: [usize; 4],
+    /// The expected number of rolls with each outcome, in the order of `RollOutcome::ALL`.
+    expected: [f64; 4],
+    /// The average highest die, not counting rolls of zero dice, which keep the lowest die.
+    average_result: Option<f64>,
+    checks: Vec<(String, usize)>,
+}
+
+impl RollStats {
+    /// Get the statistics for the rolls made in a channel, optionally only by one user and only
+    /// since the channel's current session started.
+    pub fn get(
+        connection: &Connection,
+        channel_id: ChannelId,
+        user_id: Option<UserId>,
+        session: bool,
+    ) -> RusqliteResult<RollStats> {
+        let channel_id = channel_id.to_string();
+        let user_id = user_id.map(|user_id| user_id.to_string());
+        let params: &[&dyn ToSql] = &[&channel_id, &user_id, &session];
+
+        let mut outcomes = [0; 4];
+        let mut statement = connection.prepare(&format!(
+            "SELECT outcome, COUNT(*) FROM rolls WHERE {} GROUP BY outcome",
+            CONDITION
+        ))?;
+        let counts = statement.query_map(params, |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        for count in counts {
+            let (outcome, count) = count?;
+            if let Some(index) = RollOutcome::parse(&outcome)
+                .and_then(|outcome| RollOutcome::ALL.iter().position(|other| *other == outcome))
+            {
+                outcomes[index] += count.try_into().unwrap_or(0);
+            }
+        }
+
+        let mut expected = [0.0; 4];
+        let mut statement = connection.prepare(&format!(
+            "SELECT pool, mode, COUNT(*) FROM rolls WHERE {} GROUP BY pool, mode",
+            CONDITION
+        ))?;
+        let pools = statement.query_map(params, |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+        for pool in pools {
+            let (pool, mode, count) = pool?;
+            let odds = Roll::new_unsafe(pool.max(0) as usize)
+                .with_mode(RollMode::parse(&mode).unwrap_or(RollMode::Normal))
+                .odds();
+            for (index, outcome) in RollOutcome::ALL.iter().enumerate() {
+                expected[index] += odds.chance(*outcome) * count as f64;
+            }
+        }
+
+        let average_result = connection.query_row(
+            &format!(
+                "SELECT AVG(result) FROM rolls WHERE {} AND pool > 0",
+                CONDITION
+            ),
+            params,
+            |row| row.get(0),
+        )?;
+
+        let mut statement = connection.prepare(&format!(
+            "SELECT check_name, COUNT(*) AS count \
+             FROM rolls \
+             WHERE {} \
+             AND check_name IS NOT NULL \
+             GROUP BY check_name \
+             ORDER BY count DESC, check_name \
+             LIMIT {}",
+            CONDITION, MAXIMUM_STATS_CHECKS
+        ))?;
+        let checks = statement
+            .query_map(params, |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?.try_into().unwrap_or(0),
+                ))
+            })?
+            .collect::<RusqliteResult<Vec<_>>>()?;
+
+        Ok(RollStats {
+            outcomes,
+            expected,
+            average_result,
+            checks,
+        })
+    }
+
+    /// Get the users who have rolled in a channel, optionally only since the channel's current
+    /// session started, from the most rolls to the fewest, with the name each user last rolled
+    /// under.
+    pub fn get_users(
+        connection: &Connection,
+        channel_id: ChannelId,
+        session: bool,
+    ) -> RusqliteResult<Vec<(UserId, Option<String>)>> {
+        let params: &[&dyn ToSql] = &[&channel_id.to_string(), &None::<String>, &session];
+        // SQLite takes the bare `user_name` column from the row with the greatest `roll_id`.
+        let mut statement = connection.prepare(&format!(
+            "SELECT user_id, user_name, MAX(roll_id) \
+             FROM rolls \
+             WHERE {} \
+             GROUP BY user_id \
+             ORDER BY COUNT(*) DESC",
+            CONDITION
+        ))?;
+        let users = statement
+            .query_map(params, |row| {
+                Ok((
+                    UserId(row.get::<_, String>(0)?.parse::<u64>().unwrap_or(0)),
+                    row.get(1)?,
+                ))
+            })?
+            .collect();
+        users
+    }
+
+    /// The total number of rolls.
+    pub fn rolls(&self) -> usize {
+        self.outcomes.iter().sum()
+    }
+
+    /// A one line summary of the outcomes rolled.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} roll{} ({}), average highest die {}",
+            self.rolls(),
+            if self.rolls() == 1 { "" } else { "s" },
+            RollOutcome::ALL
+                .iter()
+                .zip(self.outcomes.iter())
+                .map(|(outcome, count)| format!("{} {}", count, outcome.as_str()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.average_result
+                .map_or("-".to_owned(), |average| format!("{:.1}", average))
+        )
+    }
+}
+
+impl fmt::Display for RollStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rolls = self.rolls();
+        let percentage = |count: f64| {
+            if rolls > 0 {
+                count * 100.0 / rolls as f64
+            } else {
+                0.0
+            }
+        };
+        write!(
+            f,
+            "**{}** roll{}, with an average highest die of **{}**.",
+            rolls,
+            if rolls == 1 { "" } else { "s" },
+            self.average_result
+                .map_or("-".to_owned(), |average| format!("{:.1}", average))
+        )?;
+        write!(
+            f,
+            "\n```\n{:<20}{:>13}{:>15}",
+            "Outcome", "Rolled", "Expected"
+        )?;
+        for (index, outcome) in RollOutcome::ALL.iter().enumerate() {
+            let count = self.outcomes[index];
+            let expected = self.expected[index];
+            write!(
+                f,
+                "\n{:<20}{:>5} ({:>5.1}%){:>7.1} ({:>5.1}%)",
+                outcome.name(RollKind::Action),
+                count,
+                percentage(count as f64),
+                expected,
+                percentage(expected)
+            )?;
+        }
+        write!(f, "\n```")?;
+        if !self.checks.is_empty() {
+            write!(
+                f,
+                "\nMost used: {}",
+                self.checks
+                    .iter()
+                    .map(|(check, count)| format!("{} ({})", check, count))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
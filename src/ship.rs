@@ -0,0 +1,313 @@
+use rusqlite::types::ToSql;
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, OptionalExtension, Row};
+use serenity::model::id::ChannelId;
+use std::convert::TryInto;
+use std::fmt;
+
+/// The maximum rating that a ship system may have.
+pub const MAXIMUM_SYSTEM_RATING: usize = 4;
+
+/// The ship shared by the crew playing in a channel.
+///
+/// The ship has a rating for each of its systems, which may be rolled as a dice pool, along with
+/// the crew's tier, upgrades and resources: gambits and cred.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Ship {
+    // System ratings
+    engines: i32,
+    hull: i32,
+    comms: i32,
+    weapons: i32,
+
+    tier: i32,
+
+    // Resources
+    gambits: i32,
+    cred: i32,
+
+    upgrades: Vec<String>,
+}
+
+impl Ship {
+    pub fn get(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<Option<Ship>> {
+        connection
+            .query_row(
+                "SELECT engines, hull, comms, weapons, tier, gambits, cred \
+                 FROM ships \
+                 WHERE channel_id = $1",
+                &[&channel_id.to_string()],
+                Ship::from_row,
+            )
+            .optional()
+            .and_then(|ship| match ship {
+                Some(ship) => Ok(Some(Ship {
+                    upgrades: Ship::get_upgrades(connection, channel_id)?,
+                    ..ship
+                })),
+                None => Ok(None),
+            })
+    }
+
+    /// Create a ship with every system rating and resource set to zero.
+    ///
+    /// Returns the number of rows inserted, which is zero if the channel already has a ship.
+    pub fn create(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<usize> {
+        connection.execute(
+            "INSERT OR IGNORE INTO ships (channel_id) VALUES ($1)",
+            &[&channel_id.to_string()],
+        )
+    }
+
+    /// Update a single system rating of a ship.
+    ///
+    /// Returns the number of rows updated, which is zero if the channel has no ship.
+    pub fn set_system(
+        connection: &Connection,
+        channel_id: ChannelId,
+        name: SystemName,
+        rating: usize,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&(rating as i32), &channel_id.to_string()];
+        connection.execute(
+            &format!(
+                "UPDATE ships SET {} = $1 WHERE channel_id = $2",
+                name.column_name()
+            ),
+            params,
+        )
+    }
+
+    /// Update the tier of a ship's crew.
+    ///
+    /// Returns the number of rows updated, which is zero if the channel has no ship.
+    pub fn set_tier(
+        connection: &Connection,
+        channel_id: ChannelId,
+        tier: usize,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&(tier as i32), &channel_id.to_string()];
+        connection.execute("UPDATE ships SET tier = $1 WHERE channel_id = $2", params)
+    }
+
+    /// Update a single resource of a ship's crew.
+    ///
+    /// Returns the number of rows updated, which is zero if the channel has no ship.
+    pub fn set_resource(
+        connection: &Connection,
+        channel_id: ChannelId,
+        name: ResourceName,
+        amount: usize,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&(amount as i32), &channel_id.to_string()];
+        connection.execute(
+            &format!(
+                "UPDATE ships SET {} = $1 WHERE channel_id = $2",
+                name.column_name()
+            ),
+            params,
+        )
+    }
+
+    /// Add gambits to, or if negative spend gambits from, a ship's crew.
+    ///
+    /// Returns the number of rows updated, which is zero if the channel has no ship.
+    pub fn add_gambits(
+        connection: &Connection,
+        channel_id: ChannelId,
+        amount: i32,
+    ) -> RusqliteResult<usize> {
+        let params: &[&dyn ToSql] = &[&amount, &channel_id.to_string()];
+        connection.execute(
+            "UPDATE ships SET gambits = MAX(gambits + $1, 0) WHERE channel_id = $2",
+            params,
+        )
+    }
+
+    /// Add an upgrade to a ship.
+    ///
+    /// Returns the number of upgrades added, which is zero if the ship already has the upgrade.
+    pub fn add_upgrade(
+        connection: &Connection,
+        channel_id: ChannelId,
+        upgrade: &str,
+    ) -> RusqliteResult<usize> {
+        connection.execute(
+            "INSERT OR IGNORE INTO ship_upgrades (channel_id, upgrade) VALUES ($1, $2)",
+            &[&channel_id.to_string(), upgrade],
+        )
+    }
+
+    /// Remove an upgrade from a ship.
+    ///
+    /// Returns the number of upgrades removed, which is zero if the ship didn't have the upgrade.
+    pub fn remove_upgrade(
+        connection: &Connection,
+        channel_id: ChannelId,
+        upgrade: &str,
+    ) -> RusqliteResult<usize> {
+        connection.execute(
+            "DELETE FROM ship_upgrades WHERE channel_id = $1 AND upgrade = $2",
+            &[&channel_id.to_string(), upgrade],
+        )
+    }
+
+    fn get_upgrades(connection: &Connection, channel_id: ChannelId) -> RusqliteResult<Vec<String>> {
+        let mut statement = connection
+            .prepare("SELECT upgrade FROM ship_upgrades WHERE channel_id = $1 ORDER BY rowid")?;
+        let upgrades = statement
+            .query_map(&[&channel_id.to_string()], |row| row.get("upgrade"))?
+            .collect();
+        upgrades
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Ship> {
+        Ok(Ship {
+            engines: row.get("engines")?,
+            hull: row.get("hull")?,
+            comms: row.get("comms")?,
+            weapons: row.get("weapons")?,
+            tier: row.get("tier")?,
+            gambits: row.get("gambits")?,
+            cred: row.get("cred")?,
+            upgrades: Vec::new(),
+        })
+    }
+
+    pub fn system(&self, name: SystemName) -> usize {
+        match name {
+            SystemName::Engines => self.engines,
+            SystemName::Hull => self.hull,
+            SystemName::Comms => self.comms,
+            SystemName::Weapons => self.weapons,
+        }
+        .try_into()
+        .unwrap_or(0)
+    }
+
+    pub fn tier(&self) -> usize {
+        self.tier.try_into().unwrap_or(0)
+    }
+
+    pub fn resource(&self, name: ResourceName) -> usize {
+        match name {
+            ResourceName::Cred => self.cred,
+            ResourceName::Gambits => self.gambits,
+        }
+        .try_into()
+        .unwrap_or(0)
+    }
+}
+
+impl fmt::Display for Ship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "**Systems**")?;
+        for system in SystemName::ALL.iter() {
+            write!(f, " {} ", system.as_str())?;
+            for index in 0..MAXIMUM_SYSTEM_RATING {
+                write!(
+                    f,
+                    "{}",
+                    if index < self.system(*system) {
+                        "●"
+                    } else {
+                        "○"
+                    }
+                )?;
+            }
+        }
+        write!(f, "\n**Upgrades** ")?;
+        if self.upgrades.is_empty() {
+            write!(f, "None")?;
+        } else {
+            for (index, upgrade) in self.upgrades.iter().enumerate() {
+                write!(f, "{}{}", if index > 0 { ", " } else { "" }, upgrade)?;
+            }
+        }
+        write!(
+            f,
+            "\n**Tier** {} **Gambits** {} **Cred** {}",
+            self.tier(),
+            self.resource(ResourceName::Gambits),
+            self.resource(ResourceName::Cred)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SystemName {
+    Engines,
+    Hull,
+    Comms,
+    Weapons,
+}
+
+impl SystemName {
+    pub const ALL: [SystemName; 4] = [
+        SystemName::Engines,
+        SystemName::Hull,
+        SystemName::Comms,
+        SystemName::Weapons,
+    ];
+
+    pub fn parse(string: &str) -> Option<SystemName> {
+        match string.to_lowercase().as_ref() {
+            "engines" => Some(SystemName::Engines),
+            "hull" => Some(SystemName::Hull),
+            "comms" => Some(SystemName::Comms),
+            "weapons" => Some(SystemName::Weapons),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            SystemName::Engines => "Engines",
+            SystemName::Hull => "Hull",
+            SystemName::Comms => "Comms",
+            SystemName::Weapons => "Weapons",
+        }
+    }
+
+    /// The name of the column in the `ships` table that stores this system's rating.
+    fn column_name(&self) -> &str {
+        match self {
+            SystemName::Engines => "engines",
+            SystemName::Hull => "hull",
+            SystemName::Comms => "comms",
+            SystemName::Weapons => "weapons",
+        }
+    }
+}
+
+/// A resource shared by a ship's crew.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ResourceName {
+    Cred,
+    Gambits,
+}
+
+impl ResourceName {
+    pub fn parse(string: &str) -> Option<ResourceName> {
+        match string.to_lowercase().as_ref() {
+            "cred" => Some(ResourceName::Cred),
+            "gambit" | "gambits" => Some(ResourceName::Gambits),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ResourceName::Cred => "Cred",
+            ResourceName::Gambits => "Gambits",
+        }
+    }
+
+    /// The name of the column in the `ships` table that stores this resource.
+    fn column_name(&self) -> &str {
+        match self {
+            ResourceName::Cred => "cred",
+            ResourceName::Gambits => "gambits",
+        }
+    }
+}
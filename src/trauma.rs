@@ -0,0 +1,102 @@
+use rusqlite::types::{ToSql, Type};
+use rusqlite::Result as RusqliteResult;
+use rusqlite::{Connection, Error as RusqliteError, Row};
+
+/// The number of traumas after which a character must retire.
+pub const MAXIMUM_TRAUMAS: usize = 4;
+
+/// A trauma condition that a character suffers when their stress overflows.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Trauma {
+    Cold,
+    Haunted,
+    Obsessed,
+    Paranoid,
+    Reckless,
+    Soft,
+    Unstable,
+    Vicious,
+}
+
+impl Trauma {
+    pub const ALL: [Trauma; 8] = [
+        Trauma::Cold,
+        Trauma::Haunted,
+        Trauma::Obsessed,
+        Trauma::Paranoid,
+        Trauma::Reckless,
+        Trauma::Soft,
+        Trauma::Unstable,
+        Trauma::Vicious,
+    ];
+
+    /// Get the traumas that a character suffers, in the order they were suffered.
+    pub fn get_all(connection: &Connection, character_id: i64) -> RusqliteResult<Vec<Trauma>> {
+        let mut statement = connection
+            .prepare("SELECT trauma FROM traumas WHERE character_id = $1 ORDER BY rowid")?;
+        let traumas = statement
+            .query_map(&[&character_id], Trauma::from_row)?
+            .collect();
+        traumas
+    }
+
+    /// Mark a trauma on a character, using up one of their pending traumas.
+    ///
+    /// Returns the number of traumas marked, which is zero if the character already suffers the
+    /// trauma.
+    pub fn add(
+        connection: &mut Connection,
+        character_id: i64,
+        trauma: Trauma,
+    ) -> RusqliteResult<usize> {
+        let transaction = connection.transaction()?;
+        let params: &[&dyn ToSql] = &[&character_id, &trauma.as_str().to_lowercase()];
+        let inserted = transaction.execute(
+            "INSERT OR IGNORE INTO traumas (character_id, trauma) VALUES ($1, $2)",
+            params,
+        )?;
+        if inserted > 0 {
+            transaction.execute(
+                "UPDATE characters \
+                 SET pending_trauma = MAX(pending_trauma - 1, 0) \
+                 WHERE character_id = $1",
+                &[&character_id],
+            )?;
+        }
+        transaction.commit().map(|_| inserted)
+    }
+
+    fn from_row(row: &Row) -> RusqliteResult<Trauma> {
+        row.get::<_, String>("trauma").and_then(|trauma| {
+            Trauma::parse(&trauma)
+                .ok_or_else(|| RusqliteError::InvalidColumnType(0, trauma, Type::Text))
+        })
+    }
+
+    pub fn parse(string: &str) -> Option<Trauma> {
+        match string.to_lowercase().as_ref() {
+            "cold" => Some(Trauma::Cold),
+            "haunted" => Some(Trauma::Haunted),
+            "obsessed" => Some(Trauma::Obsessed),
+            "paranoid" => Some(Trauma::Paranoid),
+            "reckless" => Some(Trauma::Reckless),
+            "soft" => Some(Trauma::Soft),
+            "unstable" => Some(Trauma::Unstable),
+            "vicious" => Some(Trauma::Vicious),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Trauma::Cold => "Cold",
+            Trauma::Haunted => "Haunted",
+            Trauma::Obsessed => "Obsessed",
+            Trauma::Paranoid => "Paranoid",
+            Trauma::Reckless => "Reckless",
+            Trauma::Soft => "Soft",
+            Trauma::Unstable => "Unstable",
+            Trauma::Vicious => "Vicious",
+        }
+    }
+}